@@ -0,0 +1,171 @@
+//! Compare the indexed slot storage used by `Inventory` against the
+//! `LinkedList` it replaced, for add, find, and remove workloads.
+//!
+//! Run with `cargo bench --bench slots`.
+
+use std::collections::LinkedList;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use rust_inventory::prelude::*;
+
+///
+/// The original list-backed Inventory, reduced to the operations being
+/// measured.
+///
+struct ListInventory {
+    slots: LinkedList<ItemStack>,
+    capacity: usize,
+}
+
+impl ListInventory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: LinkedList::new(),
+            capacity,
+        }
+    }
+
+    fn find_matching_item_stack(&mut self, key: &ItemStack) -> Option<&mut ItemStack> {
+        self.slots.iter_mut().find(|stack| stack.is_same_kind(key))
+    }
+
+    fn add_items(&mut self, stack: ItemStack) -> bool {
+        if let Some(the_match) = self.find_matching_item_stack(&stack) {
+            the_match.add_items(stack.size());
+            return true;
+        }
+
+        if self.slots.len() < self.capacity {
+            self.slots.push_back(stack);
+            return true;
+        }
+
+        false
+    }
+
+    fn remove_stack(&mut self, id: u64) -> Option<ItemStack> {
+        let index = self
+            .slots
+            .iter()
+            .position(|stack| stack.get_item().get_id() == id)?;
+
+        let mut tail = self.slots.split_off(index);
+        let taken = tail.pop_front();
+        self.slots.append(&mut tail);
+
+        taken
+    }
+}
+
+fn stacks(n: usize) -> Vec<ItemStack> {
+    (0..n as u64)
+        .map(|id| ItemStack::new(Item::new(id, format!("Item {}", id)), 1))
+        .collect()
+}
+
+fn filled_inventory(stacks: &[ItemStack]) -> Inventory {
+    let mut inv = Inventory::new(stacks.len());
+    for stack in stacks.iter().cloned() {
+        inv.add_items(stack);
+    }
+    inv
+}
+
+fn filled_list(stacks: &[ItemStack]) -> ListInventory {
+    let mut inv = ListInventory::new(stacks.len());
+    for stack in stacks.iter().cloned() {
+        inv.add_items(stack);
+    }
+    inv
+}
+
+fn bench_add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+
+    for n in [100, 1_000, 5_000] {
+        let to_add = stacks(n);
+
+        group.bench_with_input(BenchmarkId::new("indexed", n), &to_add, |b, to_add| {
+            b.iter(|| filled_inventory(black_box(to_add)))
+        });
+        group.bench_with_input(BenchmarkId::new("linked_list", n), &to_add, |b, to_add| {
+            b.iter(|| filled_list(black_box(to_add)).slots.len())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find");
+
+    for n in [100, 1_000, 5_000] {
+        let keys = stacks(n);
+        let mut inv = filled_inventory(&keys);
+        let mut list = filled_list(&keys);
+
+        group.bench_with_input(BenchmarkId::new("indexed", n), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .filter(|key| inv.find_matching_item_stack(key).is_some())
+                    .count()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("linked_list", n), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .filter(|key| list.find_matching_item_stack(key).is_some())
+                    .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+///
+/// Visit every id below n in a scattered (but deterministic) order, so that
+/// removals do not always hit the front of the list.
+///
+fn scattered_ids(n: usize) -> Vec<u64> {
+    (0..n as u64).map(|i| (i * 7_919) % n as u64).collect()
+}
+
+fn bench_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove");
+
+    for n in [100, 1_000, 5_000] {
+        let to_add = stacks(n);
+        let ids = scattered_ids(n);
+
+        group.bench_with_input(BenchmarkId::new("indexed", n), &to_add, |b, to_add| {
+            b.iter_batched(
+                || filled_inventory(to_add),
+                |mut inv| {
+                    for id in ids.iter() {
+                        let _ = black_box(inv.remove_items(*id, 1));
+                    }
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("linked_list", n), &to_add, |b, to_add| {
+            b.iter_batched(
+                || filled_list(to_add),
+                |mut list| {
+                    for id in ids.iter() {
+                        black_box(list.remove_stack(*id));
+                    }
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_add, bench_find, bench_remove);
+criterion_main!(benches);
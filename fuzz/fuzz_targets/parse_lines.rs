@@ -0,0 +1,20 @@
+//! Feed arbitrary bytes to both file parsers.
+//!
+//! Run with `cargo +nightly fuzz run parse_lines` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use rust_inventory::parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = Parser::read_inventory_lines(data);
+    for line in lines.iter() {
+        if let Some(error) = line.error() {
+            let _ = error.to_string();
+        }
+    }
+
+    let (_, errors) = Parser::read_items_with_errors(data);
+    let _ = errors.to_string();
+});
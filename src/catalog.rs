@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use crate::error::CatalogError;
+use crate::items::Item;
+
+///
+/// How an inventory file refers to an Item--by numeric id (`57`) or by
+/// quoted name (`"Diamond Block"`).
+///
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ItemRef {
+    Id(u64),
+    Name(String),
+}
+
+impl std::fmt::Display for ItemRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ItemRef::Id(id) => write!(f, "{}", id),
+            ItemRef::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+///
+/// The set of known Items, keyed by id and by name.
+///
+/// A catalog upholds the promise made by `Item`: no two entries share an
+/// id. Names are unique as well, ignoring case, so that either one can be
+/// used to refer to an Item.
+///
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Vec<Item>", try_from = "Vec<Item>")
+)]
+pub struct ItemCatalog {
+    ///
+    /// Every Item in the order it was added.
+    ///
+    items: Vec<Item>,
+
+    ///
+    /// Position in `items` for each id.
+    ///
+    by_id: HashMap<u64, usize>,
+
+    ///
+    /// Position in `items` for each lowercased name.
+    ///
+    by_name: HashMap<String, usize>,
+}
+
+impl ItemCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Build a catalog from a collection of Items.
+    ///
+    /// # Returns
+    ///
+    /// the catalog, or the first conflict found
+    ///
+    pub fn from_items<I>(items: I) -> Result<Self, CatalogError>
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let mut catalog = Self::new();
+
+        for item in items {
+            catalog.insert(item)?;
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    ///
+    /// Add an Item to the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to add
+    ///
+    /// # Returns
+    ///
+    /// `Err` describing the existing entry if the id or name is taken, in
+    /// which case the catalog is left unchanged
+    ///
+    pub fn insert(&mut self, item: Item) -> Result<(), CatalogError> {
+        if let Some(existing) = self.get(item.get_id()) {
+            return Err(CatalogError::DuplicateId {
+                id: item.get_id(),
+                existing: existing.get_name().to_owned(),
+            });
+        }
+
+        if let Some(existing) = self.find_by_name(item.get_name()) {
+            return Err(CatalogError::DuplicateName {
+                name: item.get_name().to_owned(),
+                existing: existing.get_id(),
+            });
+        }
+
+        let position = self.items.len();
+        self.by_id.insert(item.get_id(), position);
+        self.by_name
+            .insert(item.get_name().to_lowercase(), position);
+        self.items.push(item);
+
+        Ok(())
+    }
+
+    ///
+    /// Look up an Item by id.
+    ///
+    pub fn get(&self, id: u64) -> Option<&Item> {
+        self.by_id.get(&id).map(|&position| &self.items[position])
+    }
+
+    ///
+    /// Look up an Item by name, ignoring case.
+    ///
+    pub fn find_by_name(&self, name: &str) -> Option<&Item> {
+        self.by_name
+            .get(&name.to_lowercase())
+            .map(|&position| &self.items[position])
+    }
+
+    ///
+    /// Look up the Item a reference points to.
+    ///
+    pub fn resolve(&self, item_ref: &ItemRef) -> Option<&Item> {
+        match item_ref {
+            ItemRef::Id(id) => self.get(*id),
+            ItemRef::Name(name) => self.find_by_name(name),
+        }
+    }
+
+    ///
+    /// Find the Item a reference that does not resolve was most likely
+    /// meant to name--e.g., `Diamond Blok` for `Diamond Block`, or `75`
+    /// for `57`.
+    ///
+    /// Names are compared ignoring case and may be off by one edit per
+    /// three characters (but at least one). Ids of two or more digits may
+    /// have one digit wrong or two neighbouring digits swapped; single
+    /// digit ids are never close enough to guess at.
+    ///
+    /// # Returns
+    ///
+    /// the closest Item, or `None` if nothing is close enough
+    ///
+    pub fn suggest(&self, item_ref: &ItemRef) -> Option<&Item> {
+        match item_ref {
+            ItemRef::Id(id) => {
+                let wanted = id.to_string();
+
+                self.items
+                    .iter()
+                    .find(|item| is_id_typo(&wanted, &item.get_id().to_string()))
+            }
+            ItemRef::Name(name) => {
+                let wanted = name.to_lowercase();
+                let limit = (wanted.chars().count() / 3).max(1);
+
+                self.items
+                    .iter()
+                    .map(|item| {
+                        (
+                            edit_distance(&wanted, &item.get_name().to_lowercase()),
+                            item,
+                        )
+                    })
+                    .filter(|(distance, _)| *distance <= limit)
+                    .min_by_key(|(distance, _)| *distance)
+                    .map(|(_, item)| item)
+            }
+        }
+    }
+
+    ///
+    /// Iterate over all Items in the order they were added.
+    ///
+    pub fn iter(&self) -> std::slice::Iter<'_, Item> {
+        self.items.iter()
+    }
+
+    ///
+    /// Retrieve all Items in the order they were added.
+    ///
+    pub fn into_items(self) -> Vec<Item> {
+        self.items
+    }
+}
+
+impl From<ItemCatalog> for Vec<Item> {
+    fn from(catalog: ItemCatalog) -> Self {
+        catalog.into_items()
+    }
+}
+
+impl TryFrom<Vec<Item>> for ItemCatalog {
+    type Error = CatalogError;
+
+    fn try_from(items: Vec<Item>) -> Result<Self, Self::Error> {
+        Self::from_items(items)
+    }
+}
+
+impl<'a> IntoIterator for &'a ItemCatalog {
+    type Item = &'a Item;
+    type IntoIter = std::slice::Iter<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///
+/// Count the single-character insertions, deletions, and substitutions
+/// needed to turn one string into another (Levenshtein distance).
+///
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut previous: Vec<usize> = (0..=rhs.len()).collect();
+
+    for (row, lhs_ch) in lhs.chars().enumerate() {
+        let mut current = vec![row + 1; rhs.len() + 1];
+
+        for (col, rhs_ch) in rhs.iter().enumerate() {
+            let substitution = previous[col] + usize::from(lhs_ch != *rhs_ch);
+            current[col + 1] = substitution
+                .min(previous[col + 1] + 1)
+                .min(current[col] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[rhs.len()]
+}
+
+///
+/// Determine whether two ids of the same length (at least two digits)
+/// differ by a single mistyped digit or a single swap of neighbouring
+/// digits.
+///
+fn is_id_typo(lhs: &str, rhs: &str) -> bool {
+    if lhs.len() != rhs.len() || lhs.len() < 2 {
+        return false;
+    }
+
+    let differences: Vec<usize> = lhs
+        .bytes()
+        .zip(rhs.bytes())
+        .enumerate()
+        .filter(|(_, (l, r))| l != r)
+        .map(|(offset, _)| offset)
+        .collect();
+
+    match differences.as_slice() {
+        [_] => true,
+        [first, second] => {
+            *second == first + 1
+                && lhs.as_bytes()[*first] == rhs.as_bytes()[*second]
+                && lhs.as_bytes()[*second] == rhs.as_bytes()[*first]
+        }
+        _ => false,
+    }
+}
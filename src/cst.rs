@@ -0,0 +1,377 @@
+use std::ops::Range;
+
+use crate::error::EditError;
+use crate::parser::{ParsedLine, Parser, ATTRIBUTE_SEPARATOR, COMMENT_MARKER};
+
+///
+/// What a run of characters on a line is.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Spaces and tabs between other tokens
+    Whitespace,
+
+    /// The leading `#` or `-` that says what kind of line this is
+    Directive,
+
+    /// A bare number--an id, quantity, capacity, or weight
+    Number,
+
+    /// An item name in double quotes, quotes included
+    QuotedName,
+
+    /// A `key=value` attribute
+    Attribute,
+
+    /// A `//` comment, running to the end of the line
+    Comment,
+
+    /// Anything else
+    Text,
+}
+
+///
+/// One run of characters on a line. The span holds byte offsets into the
+/// line's text.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+///
+/// One line of an inventory file, exactly as written.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentLine {
+    /// Line content without its line ending
+    text: String,
+
+    /// `"\n"` or `"\r\n"`--or, for a final line, possibly `"\r"` or `""`
+    ending: &'static str,
+
+    /// Every character of `text` belongs to exactly one token
+    tokens: Vec<Token>,
+}
+
+impl DocumentLine {
+    fn new(text: String, ending: &'static str) -> Self {
+        let tokens = lex(&text);
+
+        DocumentLine {
+            text,
+            ending,
+            tokens,
+        }
+    }
+
+    ///
+    /// Retrieve the content of the line without its line ending.
+    ///
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///
+    /// Retrieve the line ending--`"\n"` or `"\r\n"`. A final line may also
+    /// end in a lone `"\r"` or nothing at all.
+    ///
+    pub fn ending(&self) -> &str {
+        self.ending
+    }
+
+    ///
+    /// Retrieve the tokens that make up the line, whitespace included.
+    ///
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    ///
+    /// Retrieve the text of one token.
+    ///
+    pub fn token_text(&self, token: &Token) -> &str {
+        &self.text[token.span.clone()]
+    }
+
+    ///
+    /// Retrieve the comment at the end of the line--e.g., `// spare` in
+    /// `- 4 3  // spare`--or the whole line if it is a comment.
+    ///
+    pub fn comment(&self) -> Option<&str> {
+        self.tokens
+            .iter()
+            .find(|token| token.kind == TokenKind::Comment)
+            .map(|token| self.token_text(token))
+    }
+
+    ///
+    /// Interpret the line the same way `Parser::read_inventory_lines`
+    /// would.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_number` - 1-based line number used in any error
+    ///
+    pub fn parsed(&self, line_number: usize) -> ParsedLine {
+        Parser::parse_inventory_line(line_number, &self.text)
+    }
+
+    ///
+    /// Replace the text of one token, leaving the rest of the line as is.
+    ///
+    fn replace_token(&mut self, token_index: usize, replacement: &str) {
+        let span = self.tokens[token_index].span.clone();
+        self.text.replace_range(span, replacement);
+        self.tokens = lex(&self.text);
+    }
+
+    ///
+    /// Find the token holding the quantity of a stack line--the third
+    /// token that is not whitespace.
+    ///
+    fn quantity_token(&self) -> Option<usize> {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| token.kind != TokenKind::Whitespace)
+            .nth(2)
+            .map(|(index, _)| index)
+    }
+}
+
+///
+/// A lossless representation of an inventory file.
+///
+/// Unlike `Parser::read_inventory_lines`, which keeps only what each line
+/// means, a document keeps every byte: spacing and alignment, comments
+/// (including those after a stack), and line endings. Writing it back out
+/// with `to_string` reproduces the input exactly, and edits change only
+/// the text they touch--so a data file under version control gets a
+/// minimal diff.
+///
+/// Lines are addressed by 0-based index.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InventoryDocument {
+    lines: Vec<DocumentLine>,
+}
+
+impl InventoryDocument {
+    ///
+    /// Split inventory file text into lines and tokens. This never fails:
+    /// lines that do not parse are kept as they are.
+    ///
+    pub fn parse(text: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let (line, ending, remainder) = match rest.find('\n') {
+                Some(end) if rest[..end].ends_with('\r') => {
+                    (&rest[..end - 1], "\r\n", &rest[end + 1..])
+                }
+                Some(end) => (&rest[..end], "\n", &rest[end + 1..]),
+                None => match rest.strip_suffix('\r') {
+                    Some(line) => (line, "\r", ""),
+                    None => (rest, "", ""),
+                },
+            };
+
+            lines.push(DocumentLine::new(line.to_owned(), ending));
+            rest = remainder;
+        }
+
+        InventoryDocument { lines }
+    }
+
+    ///
+    /// Retrieve the number of lines in the document.
+    ///
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    ///
+    /// Determine whether the document has no lines at all.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    ///
+    /// Retrieve every line in file order.
+    ///
+    pub fn lines(&self) -> &[DocumentLine] {
+        &self.lines
+    }
+
+    ///
+    /// Interpret every line--the same result as
+    /// `Parser::read_inventory_lines` on the document's text.
+    ///
+    pub fn parsed_lines(&self) -> Vec<ParsedLine> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| line.parsed(index + 1))
+            .collect()
+    }
+
+    ///
+    /// Change the quantity on a stack line, keeping its spacing and any
+    /// trailing comment.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - 0-based index of the line
+    ///
+    /// * `quantity` - replacement quantity
+    ///
+    pub fn set_quantity(&mut self, index: usize, quantity: usize) -> Result<(), EditError> {
+        let line = self.line_mut(index)?;
+
+        let quantity_token = match line.parsed(index + 1) {
+            ParsedLine::ItemStackLine { .. } => line.quantity_token(),
+            _ => None,
+        }
+        .ok_or(EditError::NotAStackLine { index })?;
+
+        line.replace_token(quantity_token, &quantity.to_string());
+
+        Ok(())
+    }
+
+    ///
+    /// Insert a new line, using the same line ending as the rest of the
+    /// document.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - 0-based index the new line will have (at most `len()`)
+    ///
+    /// * `text` - content of the new line, which must not contain a line
+    ///   break
+    ///
+    pub fn insert_line(&mut self, index: usize, text: &str) -> Result<(), EditError> {
+        let total_lines = self.lines.len();
+        if index > total_lines {
+            return Err(EditError::LineOutOfRange { index, total_lines });
+        }
+        if text.contains('\n') {
+            return Err(EditError::LineBreak);
+        }
+
+        let ending =
+            self.lines.first().map_or(
+                "\n",
+                |line| if line.ending == "\r\n" { "\r\n" } else { "\n" },
+            );
+
+        // A new last line takes over the old last line's (missing) ending
+        let new_ending = match self.lines.last_mut() {
+            Some(last) if index == total_lines => std::mem::replace(&mut last.ending, ending),
+            _ => ending,
+        };
+
+        self.lines
+            .insert(index, DocumentLine::new(text.to_owned(), new_ending));
+
+        Ok(())
+    }
+
+    ///
+    /// Remove a line.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - 0-based index of the line
+    ///
+    /// # Returns
+    ///
+    /// the removed line
+    ///
+    pub fn remove_line(&mut self, index: usize) -> Result<DocumentLine, EditError> {
+        self.line_mut(index)?;
+        let removed = self.lines.remove(index);
+
+        // Keep a file that had no final line ending that way
+        if index == self.lines.len() {
+            if let Some(last) = self.lines.last_mut() {
+                last.ending = removed.ending;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn line_mut(&mut self, index: usize) -> Result<&mut DocumentLine, EditError> {
+        let total_lines = self.lines.len();
+
+        self.lines
+            .get_mut(index)
+            .ok_or(EditError::LineOutOfRange { index, total_lines })
+    }
+}
+
+impl std::fmt::Display for InventoryDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for line in self.lines.iter() {
+            write!(f, "{}{}", line.text, line.ending)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Split a line into tokens, classifying each one. Unlike `tokenize` in
+/// the parser, whitespace is kept, a comment runs to the end of the line,
+/// and a quoted name is one token even if it contains spaces.
+///
+fn lex(line: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while offset < line.len() {
+        let rest = &line[offset..];
+        let is_first_word = !tokens
+            .iter()
+            .any(|token: &Token| token.kind != TokenKind::Whitespace);
+
+        let (kind, len) = if rest.starts_with(char::is_whitespace) {
+            let len = rest
+                .find(|ch: char| !ch.is_whitespace())
+                .unwrap_or(rest.len());
+            (TokenKind::Whitespace, len)
+        } else if rest.starts_with(COMMENT_MARKER) {
+            (TokenKind::Comment, rest.len())
+        } else if let Some(quoted) = rest.strip_prefix('"') {
+            let len = quoted.find('"').map_or(rest.len(), |end| end + 2);
+            (TokenKind::QuotedName, len)
+        } else {
+            let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let word = &rest[..len];
+
+            let kind = if is_first_word && (word == "#" || word == "-") {
+                TokenKind::Directive
+            } else if word.contains(ATTRIBUTE_SEPARATOR) {
+                TokenKind::Attribute
+            } else if word.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+                TokenKind::Number
+            } else {
+                TokenKind::Text
+            };
+            (kind, len)
+        };
+
+        tokens.push(Token {
+            kind,
+            span: offset..offset + len,
+        });
+        offset += len;
+    }
+
+    tokens
+}
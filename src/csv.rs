@@ -0,0 +1,398 @@
+//! CSV import and export.
+//!
+//! # Item catalog
+//!
+//! `Parser::read_catalog_csv` reads a catalog from a spreadsheet export.
+//! The first row names the columns, in any order. `id` and `name` are
+//! required; the other columns are optional and use the same names and
+//! values as the item file attributes.
+//!
+//! ```text
+//! id,name,stack,weight,category,tags
+//! 1,HP Potion,16,,,
+//! 5,Iron Ore,,2.5,ore,"smeltable,heavy"
+//! ```
+//!
+//! An empty cell takes the same default as an omitted attribute. Columns
+//! with an empty header are ignored, as is a byte order mark at the start
+//! of the file. Blank lines are skipped. If the header row is unusable, no
+//! items are read.
+//!
+//! # Inventory contents
+//!
+//! `write_inventory_stacks` lists every occupied slot, one row each.
+//!
+//! ```text
+//! inventory,slot,id,name,quantity
+//! 0,0,1,HP Potion,10
+//! ```
+//!
+//! Fields containing a comma or quote are quoted, with quotes doubled, as
+//! spreadsheets expect. A quoted field may not span multiple lines.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use crate::catalog::ItemCatalog;
+use crate::error::{ParseError, ParseErrorKind, ParseErrors};
+use crate::inventory::Inventory;
+use crate::items::{self, Item};
+use crate::parser::{self, ItemSpans, LineError, Parser, ITEM_ATTRIBUTES};
+
+///
+/// Separates fields within a record.
+///
+const FIELD_SEPARATOR: char = ',';
+
+const QUOTE: char = '"';
+
+///
+/// Spreadsheet programs may start UTF-8 exports with this character.
+///
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+///
+/// What a column of an item catalog holds.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    Id,
+    Name,
+    Attribute(&'static str),
+
+    /// A column with an empty header, whose values are skipped
+    Ignored,
+}
+
+///
+/// The byte range (within its line) and value of one field.
+///
+pub(crate) type Field = (Range<usize>, String);
+
+///
+/// Split one CSV record into its fields. Unquoted fields are trimmed;
+/// quoted fields are kept exactly, minus the quotes.
+///
+/// # Returns
+///
+/// each field, or what went wrong and where
+///
+pub(crate) fn split_record(line: &str) -> Result<Vec<Field>, LineError> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+
+    loop {
+        let rest = &line[start..];
+        let padding = rest.len() - rest.trim_start().len();
+
+        let end = if rest.trim_start().starts_with(QUOTE) {
+            let quote_start = start + padding;
+            let mut value = String::new();
+            let mut offset = quote_start + 1;
+
+            loop {
+                let Some(pos) = line[offset..].find(QUOTE) else {
+                    return Err((
+                        ParseErrorKind::MissingToken {
+                            expected: "closing quote",
+                        },
+                        quote_start..line.len(),
+                    ));
+                };
+
+                value.push_str(&line[offset..offset + pos]);
+                offset += pos + 1;
+
+                // A doubled quote stands for a single one
+                if !line[offset..].starts_with(QUOTE) {
+                    break;
+                }
+                value.push(QUOTE);
+                offset += 1;
+            }
+
+            let padding = line[offset..].len() - line[offset..].trim_start().len();
+            if !line[offset + padding..].is_empty()
+                && !line[offset + padding..].starts_with(FIELD_SEPARATOR)
+            {
+                return Err((
+                    ParseErrorKind::MissingToken { expected: "comma" },
+                    offset..line.len(),
+                ));
+            }
+
+            fields.push((quote_start..offset, value));
+            offset + padding
+        } else {
+            let end = rest
+                .find(FIELD_SEPARATOR)
+                .map_or(line.len(), |pos| start + pos);
+            let value = line[start..end].trim();
+            let value_start = start + padding.min(end - start);
+
+            fields.push((value_start..value_start + value.len(), value.to_owned()));
+            end
+        };
+
+        if end == line.len() {
+            break;
+        }
+        start = end + 1;
+    }
+
+    Ok(fields)
+}
+
+///
+/// Read an ItemCatalog, collecting a diagnostic for every row that could
+/// not be used. `Parser::read_catalog_csv` is the public entry point.
+///
+pub(crate) fn read_catalog<B: BufRead>(ins: B) -> (ItemCatalog, ParseErrors) {
+    let mut catalog = ItemCatalog::new();
+    let mut errors = ParseErrors::new();
+    let mut first_seen: HashMap<u64, usize> = HashMap::new();
+    let mut columns: Option<Vec<Column>> = None;
+
+    for (index, line) in parser::split_lines(ins).enumerate() {
+        let line_number = index + 1;
+        let mut line = match line {
+            Ok(line) => line,
+            Err(lossy_line) => {
+                errors.push(ParseError::new(
+                    ParseErrorKind::InvalidEncoding,
+                    line_number,
+                    0..lossy_line.len(),
+                    &lossy_line,
+                ));
+                continue;
+            }
+        };
+        if index == 0 && line.starts_with(BYTE_ORDER_MARK) {
+            line.remove(0);
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = split_record(&line).and_then(|fields| match &columns {
+            None => {
+                columns = Some(parse_header(&line, &fields)?);
+                Ok(())
+            }
+            Some(columns) => {
+                let (item, spans) = parse_row(&line, columns, fields)?;
+                Parser::insert_into_catalog(&mut catalog, &mut first_seen, item, line_number, spans)
+            }
+        });
+
+        if let Err((kind, span)) = result {
+            errors.push(ParseError::new(kind, line_number, span, &line));
+
+            if columns.is_none() {
+                break;
+            }
+        }
+    }
+
+    (catalog, errors)
+}
+
+///
+/// Work out which column holds what from the header row.
+///
+fn parse_header(line: &str, fields: &[Field]) -> Result<Vec<Column>, LineError> {
+    let mut columns = Vec::new();
+
+    for (span, name) in fields.iter() {
+        let column = match name.to_lowercase().as_str() {
+            "" => Column::Ignored,
+            "id" => Column::Id,
+            "name" => Column::Name,
+            other => match ITEM_ATTRIBUTES.iter().find(|key| **key == other) {
+                Some(key) => Column::Attribute(key),
+                None => return Err((ParseErrorKind::UnknownAttribute, span.clone())),
+            },
+        };
+
+        if column != Column::Ignored && columns.contains(&column) {
+            return Err((ParseErrorKind::DuplicateColumn, span.clone()));
+        }
+        columns.push(column);
+    }
+
+    for (column, expected) in [(Column::Id, "id column"), (Column::Name, "name column")] {
+        if !columns.contains(&column) {
+            return Err((
+                ParseErrorKind::MissingToken { expected },
+                line.len()..line.len(),
+            ));
+        }
+    }
+
+    Ok(columns)
+}
+
+///
+/// Build an Item from one data row. Missing trailing fields are treated as
+/// empty.
+///
+/// # Returns
+///
+/// the item along with the spans of its id and name
+///
+fn parse_row(
+    line: &str,
+    columns: &[Column],
+    fields: Vec<Field>,
+) -> Result<(Item, ItemSpans), LineError> {
+    if let Some((span, _)) = fields.get(columns.len()) {
+        return Err((ParseErrorKind::UnknownAttribute, span.start..line.len()));
+    }
+
+    let end_of_line = line.len()..line.len();
+    let field = |wanted: Column| {
+        columns
+            .iter()
+            .position(|column| *column == wanted)
+            .and_then(|index| fields.get(index))
+            .filter(|(_, value)| !value.is_empty())
+    };
+
+    let (id_span, id) = match field(Column::Id) {
+        Some((span, raw_id)) => match raw_id.parse::<u64>() {
+            Ok(id) => (span.clone(), id),
+            Err(_) => return Err((ParseErrorKind::BadNumber, span.clone())),
+        },
+        None => {
+            return Err((
+                ParseErrorKind::MissingToken {
+                    expected: "item id",
+                },
+                end_of_line,
+            ))
+        }
+    };
+
+    let (name_span, name) = match field(Column::Name) {
+        Some((span, name)) => (
+            span.clone(),
+            name.split_whitespace().collect::<Vec<_>>().join(" "),
+        ),
+        None => {
+            return Err((
+                ParseErrorKind::MissingToken {
+                    expected: "item name",
+                },
+                end_of_line,
+            ))
+        }
+    };
+
+    let mut item = Item::new(id, name);
+    for column in columns.iter() {
+        if let (Column::Attribute(key), Some((span, value))) = (column, field(*column)) {
+            Parser::set_item_attribute(&mut item, key, value, span.clone())?;
+        }
+    }
+
+    Ok((item, (id_span, name_span)))
+}
+
+///
+/// Quote a field if it would otherwise be misread.
+///
+fn escape(field: &str) -> Cow<'_, str> {
+    let needs_quotes =
+        field.contains([FIELD_SEPARATOR, QUOTE, '\n', '\r']) || field.trim() != field;
+
+    if needs_quotes {
+        Cow::Owned(format!("\"{}\"", field.replace(QUOTE, "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+///
+/// Write one record, quoting fields as needed.
+///
+fn write_record<W: Write>(outs: &mut W, fields: &[&str]) -> io::Result<()> {
+    let escaped: Vec<Cow<str>> = fields.iter().map(|field| escape(field)).collect();
+
+    writeln!(outs, "{}", escaped.join(","))
+}
+
+///
+/// Write an item catalog in the form read by `Parser::read_catalog_csv`.
+///
+/// # Arguments
+///
+///  * `outs` - output destination
+///  * `catalog` - Items to write, in catalog order
+///
+pub fn write_catalog<W: Write>(mut outs: W, catalog: &ItemCatalog) -> io::Result<()> {
+    write_record(
+        &mut outs,
+        &["id", "name", "stack", "weight", "category", "tags"],
+    )?;
+
+    for item in catalog.iter() {
+        let max_stack = match item.get_max_stack_size() {
+            Item::UNLIMITED_STACK_SIZE => String::new(),
+            max => max.to_string(),
+        };
+        let weight = match item.get_weight() {
+            0 => String::new(),
+            grams => items::format_kilograms(grams),
+        };
+
+        write_record(
+            &mut outs,
+            &[
+                &item.get_id().to_string(),
+                item.get_name(),
+                &max_stack,
+                &weight,
+                item.get_category().unwrap_or_default(),
+                &item.get_tags().join(","),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+///
+/// Write the contents of every inventory, one row per occupied slot.
+/// Inventories and slots are numbered from zero.
+///
+/// # Arguments
+///
+///  * `outs` - output destination
+///  * `inventories` - inventories to write, in order
+///
+pub fn write_inventory_stacks<W: Write>(mut outs: W, inventories: &[Inventory]) -> io::Result<()> {
+    write_record(&mut outs, &["inventory", "slot", "id", "name", "quantity"])?;
+
+    for (index, inventory) in inventories.iter().enumerate() {
+        for (slot, stack) in inventory.iter_slots() {
+            let Some(stack) = stack else {
+                continue;
+            };
+            let item = stack.get_item();
+
+            write_record(
+                &mut outs,
+                &[
+                    &index.to_string(),
+                    &slot.to_string(),
+                    &item.get_id().to_string(),
+                    item.get_name(),
+                    &stack.size().to_string(),
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
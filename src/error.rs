@@ -1,5 +1,9 @@
+use std::ops::Range;
+
 use thiserror::Error;
 
+use crate::inventory::RejectReason;
+
 #[derive(Debug, Error, PartialEq)]
 pub struct ErrorWithValue<E: std::error::Error, V> {
     #[source]
@@ -11,4 +15,295 @@ pub struct ErrorWithValue<E: std::error::Error, V> {
 pub enum ParserError {
     #[error("{0:?}")]
     FileNotFound(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Parse(#[from] ParseErrors),
+
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+}
+
+///
+/// Machine-readable description of what went wrong on a line.
+///
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ParseErrorKind {
+    #[error("expected a number")]
+    BadNumber,
+
+    #[error("expected yes or no")]
+    BadFlag,
+
+    #[error("malformed item filter")]
+    BadFilter,
+
+    #[error("unknown directive")]
+    UnknownDirective,
+
+    #[error("unknown attribute")]
+    UnknownAttribute,
+
+    #[error("duplicate column")]
+    DuplicateColumn,
+
+    #[error("missing {expected}")]
+    MissingToken { expected: &'static str },
+
+    #[error("line is not valid UTF-8")]
+    InvalidEncoding,
+
+    #[error("duplicate id (first used on line {first_line})")]
+    DuplicateId { first_line: usize },
+
+    #[error("duplicate name (first used on line {first_line})")]
+    DuplicateName { first_line: usize },
+
+    #[error(
+        "unknown item{}",
+        suggestion.as_ref().map(|s| format!(" (did you mean {}?)", s)).unwrap_or_default()
+    )]
+    UnknownItem { suggestion: Option<String> },
+}
+
+///
+/// A problem found on a single line of an item or inventory file.
+///
+/// Line numbers are 1-based so that they match what an editor shows. The
+/// span holds 0-based byte offsets into `raw_line` and marks the offending
+/// text.
+///
+#[derive(Clone, Debug, Error, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub span: Range<usize>,
+    pub raw_line: String,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    ///
+    /// Create an error that is not (yet) associated with a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - what went wrong
+    ///
+    /// * `line` - 1-based line number
+    ///
+    /// * `span` - byte range of the offending text within `raw_line`
+    ///
+    /// * `raw_line` - the complete line as read
+    ///
+    pub fn new(kind: ParseErrorKind, line: usize, span: Range<usize>, raw_line: &str) -> Self {
+        Self {
+            file: None,
+            line,
+            span,
+            raw_line: raw_line.to_string(),
+            kind,
+        }
+    }
+
+    ///
+    /// Retrieve the text covered by the span. An empty span (e.g., for a
+    /// missing token) points at the end of the line.
+    ///
+    pub fn offending_text(&self) -> &str {
+        self.raw_line.get(self.span.clone()).unwrap_or("")
+    }
+
+    ///
+    /// Compute the 1-based column where the span starts, counting
+    /// characters rather than bytes so that it matches what an editor shows.
+    ///
+    pub fn column(&self) -> usize {
+        self.raw_line
+            .get(..self.span.start)
+            .map_or(self.span.start, |before| before.chars().count())
+            + 1
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.column(),
+            self.kind
+        )?;
+
+        match self.offending_text() {
+            "" => Ok(()),
+            text => write!(f, " `{}`", text),
+        }
+    }
+}
+
+///
+/// Collects every error found during a single parse pass so that callers
+/// can report all of them at once instead of stopping at the first.
+///
+#[derive(Clone, Debug, Default, Error, PartialEq)]
+pub struct ParseErrors {
+    errors: Vec<ParseError>,
+}
+
+impl ParseErrors {
+    ///
+    /// Create an empty collection.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Record one more error.
+    ///
+    pub fn push(&mut self, error: ParseError) {
+        self.errors.push(error);
+    }
+
+    ///
+    /// Retrieve the number of collected errors.
+    ///
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    ///
+    /// Determine whether no errors were collected.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    ///
+    /// Iterate over the collected errors in the order they were found.
+    ///
+    pub fn iter(&self) -> std::slice::Iter<'_, ParseError> {
+        self.errors.iter()
+    }
+
+    ///
+    /// Attach a file name to every collected error.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - file from which the input was read
+    ///
+    pub fn with_file(mut self, filename: &str) -> Self {
+        for error in self.errors.iter_mut() {
+            error.file = Some(filename.to_string());
+        }
+
+        self
+    }
+
+    ///
+    /// Convert into a `Result`--`Ok(value)` if no errors were collected.
+    ///
+    pub fn into_result<T>(self, value: T) -> Result<T, ParseErrors> {
+        if self.is_empty() {
+            Ok(value)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl std::fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for error in self.errors.iter() {
+            writeln!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl IntoIterator for ParseErrors {
+    type Item = ParseError;
+    type IntoIter = std::vec::IntoIter<ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ParseErrors {
+    type Item = &'a ParseError;
+    type IntoIter = std::slice::Iter<'a, ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl Extend<ParseError> for ParseErrors {
+    fn extend<I: IntoIterator<Item = ParseError>>(&mut self, iter: I) {
+        self.errors.extend(iter);
+    }
+}
+
+///
+/// A conflict between an Item and the entries already in an ItemCatalog.
+///
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum CatalogError {
+    #[error("id {id} is already used by {existing}")]
+    DuplicateId { id: u64, existing: String },
+
+    #[error("name {name} is already used by item {existing}")]
+    DuplicateName { name: String, existing: u64 },
+}
+
+///
+/// A request against an Inventory that could not be carried out.
+///
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum InventoryError {
+    #[error("only {available} of item {id} available ({requested} requested)")]
+    Shortage {
+        id: u64,
+        requested: usize,
+        available: usize,
+    },
+
+    #[error("slot {slot} does not exist (inventory has {total_slots} slots)")]
+    SlotOutOfRange { slot: usize, total_slots: usize },
+
+    #[error("stack of {quantity} of item {id} is not between 1 and {max_stack_size}")]
+    BadStackSize {
+        id: u64,
+        quantity: usize,
+        max_stack_size: usize,
+    },
+
+    #[error("destination accepted only {accepted} of {requested} of item {id} ({reason})")]
+    Rejected {
+        id: u64,
+        requested: usize,
+        accepted: usize,
+        reason: RejectReason,
+    },
+}
+
+///
+/// An edit to an InventoryDocument that could not be made.
+///
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum EditError {
+    #[error("line {index} does not exist (document has {total_lines} lines)")]
+    LineOutOfRange { index: usize, total_lines: usize },
+
+    #[error("line {index} is not a stack line")]
+    NotAStackLine { index: usize },
+
+    #[error("a line cannot contain a line break")]
+    LineBreak,
 }
@@ -0,0 +1,119 @@
+use std::ops::RangeInclusive;
+
+use crate::items::Item;
+
+///
+/// A policy that decides which Items an Inventory accepts--e.g., an ore bin
+/// that only takes ores.
+///
+/// Filters are written as a comma-separated list of terms, any one of
+/// which must match. A term is a single id (`7`), an inclusive id range
+/// (`10-19`), a category (`category:ore`), or a tag (`tag:fuel`).
+///
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub enum ItemFilter {
+    /// Every Item is accepted.
+    #[default]
+    Any,
+
+    /// Items whose id falls within the range are accepted.
+    Ids(RangeInclusive<u64>),
+
+    /// Items in the category are accepted.
+    Category(String),
+
+    /// Items carrying the tag are accepted.
+    Tag(String),
+
+    /// Items accepted by at least one of the filters are accepted.
+    AnyOf(Vec<ItemFilter>),
+}
+
+impl ItemFilter {
+    ///
+    /// Determine whether an Item passes this filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - Item to check
+    ///
+    pub fn accepts(&self, item: &Item) -> bool {
+        match self {
+            ItemFilter::Any => true,
+            ItemFilter::Ids(range) => range.contains(&item.get_id()),
+            ItemFilter::Category(category) => item.is_in_category(category),
+            ItemFilter::Tag(tag) => item.has_tag(tag),
+            ItemFilter::AnyOf(filters) => filters.iter().any(|filter| filter.accepts(item)),
+        }
+    }
+
+    ///
+    /// Parse a filter from its text form--e.g., `1-9,12`.
+    ///
+    /// # Returns
+    ///
+    /// the filter, or `None` if any term is malformed
+    ///
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut terms = text
+            .split(',')
+            .map(Self::parse_term)
+            .collect::<Option<Vec<_>>>()?;
+
+        match terms.len() {
+            1 => terms.pop(),
+            _ => Some(ItemFilter::AnyOf(terms)),
+        }
+    }
+
+    fn parse_term(term: &str) -> Option<Self> {
+        match term.split_once(':') {
+            Some(("category", category)) if !category.is_empty() => {
+                return Some(ItemFilter::Category(category.to_lowercase()))
+            }
+            Some(("tag", tag)) if !tag.is_empty() => {
+                return Some(ItemFilter::Tag(tag.to_lowercase()))
+            }
+            Some(_) => return None,
+            None => {}
+        }
+
+        let (low, high) = term.split_once('-').unwrap_or((term, term));
+        let (low, high) = (low.parse().ok()?, high.parse().ok()?);
+
+        if low > high {
+            return None;
+        }
+
+        Some(ItemFilter::Ids(low..=high))
+    }
+}
+
+impl std::fmt::Display for ItemFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ItemFilter::Any => Ok(()),
+            ItemFilter::Ids(range) if range.start() == range.end() => {
+                write!(f, "{}", range.start())
+            }
+            ItemFilter::Ids(range) => write!(f, "{}-{}", range.start(), range.end()),
+            ItemFilter::Category(category) => write!(f, "category:{}", category),
+            ItemFilter::Tag(tag) => write!(f, "tag:{}", tag),
+            ItemFilter::AnyOf(filters) => {
+                for (index, filter) in filters.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", filter)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
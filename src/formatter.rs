@@ -0,0 +1,301 @@
+use crate::cst::{InventoryDocument, TokenKind};
+use crate::error::{ParseError, ParseErrorKind, ParseErrors};
+use crate::filter::ItemFilter;
+use crate::items::{self, Item};
+use crate::parser::{self, ParsedLine, Parser, COMMENT_MARKER};
+
+///
+/// The two kinds of data file.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    /// `<id> <name> [key=value...]` lines
+    Items,
+
+    /// `# <capacity>` headers followed by `- <item> <quantity>` lines
+    Inventories,
+}
+
+impl FileKind {
+    ///
+    /// Guess the kind of a file from its first line that is neither blank
+    /// nor a comment.
+    ///
+    /// # Returns
+    ///
+    /// the kind, or `None` if the file has no such line or it fits neither
+    /// kind
+    ///
+    pub fn detect(text: &str) -> Option<Self> {
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with(COMMENT_MARKER))?;
+
+        if first.starts_with('#') || first.starts_with('-') {
+            Some(FileKind::Inventories)
+        } else if first.starts_with(|ch: char| ch.is_ascii_digit()) {
+            Some(FileKind::Items)
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// Format a data file of either kind. See `format_items` and
+/// `format_inventories`.
+///
+pub fn format(kind: FileKind, text: &str) -> Result<String, ParseErrors> {
+    match kind {
+        FileKind::Items => format_items(text),
+        FileKind::Inventories => format_inventories(text),
+    }
+}
+
+///
+/// Rewrite an item file in canonical form:
+///
+///  * items are sorted by id, and each comment moves with the item below it
+///  * ids are right-aligned, and attributes start in a common column
+///  * attributes are written as `stack=`, `weight=`, `category=`, `tags=`
+///  * comment markers are written as `// ` and blank lines are dropped
+///
+/// # Returns
+///
+/// the formatted text, or every error if any line could not be parsed--a
+/// file is only formatted if no line would be lost
+///
+pub fn format_items(text: &str) -> Result<String, ParseErrors> {
+    let (_, errors) = Parser::read_items_with_errors(text.as_bytes());
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut entries: Vec<(Vec<String>, Item)> = Vec::new();
+    let mut comments = Vec::new();
+
+    // Split exactly as the parser does. `text` is a str, so every line is
+    // valid UTF-8.
+    for line in parser::split_lines(text.as_bytes()).flatten() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with(COMMENT_MARKER) {
+            comments.push(normalize_comment(trimmed));
+        } else if !trimmed.is_empty() {
+            // The whole file parsed, so every other line holds one item
+            let item = Parser::read_items(line.as_bytes()).remove(0);
+            entries.push((std::mem::take(&mut comments), item));
+        }
+    }
+
+    entries.sort_by_key(|(_, item)| item.get_id());
+
+    let id_width = entries
+        .iter()
+        .map(|(_, item)| item.get_id().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let name_width = entries
+        .iter()
+        .filter(|(_, item)| !item_attributes(item).is_empty())
+        .map(|(_, item)| item.get_name().chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut formatted = String::new();
+    for (leading_comments, item) in entries.iter() {
+        for comment in leading_comments {
+            formatted.push_str(comment);
+            formatted.push('\n');
+        }
+
+        let attributes = item_attributes(item);
+        let line = if attributes.is_empty() {
+            format!("{:>id_width$} {}", item.get_id(), item.get_name())
+        } else {
+            format!(
+                "{:>id_width$} {:<name_width$} {}",
+                item.get_id(),
+                item.get_name(),
+                attributes.join(" ")
+            )
+        };
+
+        formatted.push_str(&line);
+        formatted.push('\n');
+    }
+
+    for comment in comments {
+        formatted.push_str(&comment);
+        formatted.push('\n');
+    }
+
+    Ok(formatted)
+}
+
+///
+/// Rewrite an inventory file in canonical form:
+///
+///  * headers are written as `# <capacity> [<kg>] [accept=<filter>]`
+///  * within each inventory, item references are left-aligned and
+///    quantities right-aligned--e.g., `- 2  5` below `- 1 10`
+///  * comment markers (including those after a stack) are written as `// `
+///    and blank lines are dropped, as in `format_items`
+///
+/// # Returns
+///
+/// the formatted text, or every error if any line could not be parsed or
+/// holds text that would be lost
+///
+pub fn format_inventories(text: &str) -> Result<String, ParseErrors> {
+    /// A line waiting for the column widths of its inventory.
+    enum Row {
+        Fixed(String),
+        Stack {
+            item: String,
+            quantity: String,
+            comment: Option<String>,
+        },
+    }
+
+    let document = InventoryDocument::parse(text);
+    let mut errors = ParseErrors::new();
+    let mut blocks: Vec<Vec<Row>> = vec![Vec::new()];
+
+    for (index, line) in document.lines().iter().enumerate() {
+        match line.parsed(index + 1) {
+            ParsedLine::InventoryLine {
+                max_size,
+                max_weight,
+                filter,
+            } => {
+                let mut header = format!("# {}", max_size);
+                if let Some(grams) = max_weight {
+                    header.push_str(&format!(" {}", items::format_kilograms(grams)));
+                }
+                if filter != ItemFilter::Any {
+                    header.push_str(&format!(" accept={}", filter));
+                }
+
+                blocks.push(vec![Row::Fixed(header)]);
+            }
+            ParsedLine::ItemStackLine { item, quantity } => {
+                // Anything after the quantity other than a comment is
+                // ignored by the parser, and would vanish here
+                let stray = line
+                    .tokens()
+                    .iter()
+                    .filter(|token| token.kind != TokenKind::Whitespace)
+                    .skip(3)
+                    .find(|token| token.kind != TokenKind::Comment);
+                if let Some(token) = stray {
+                    errors.push(ParseError::new(
+                        ParseErrorKind::UnknownAttribute,
+                        index + 1,
+                        token.span.clone(),
+                        line.text(),
+                    ));
+                    continue;
+                }
+
+                if let Some(block) = blocks.last_mut() {
+                    block.push(Row::Stack {
+                        item: item.to_string(),
+                        quantity: quantity.to_string(),
+                        comment: line.comment().map(normalize_comment),
+                    });
+                }
+            }
+            ParsedLine::CommentLine { .. } => {
+                let comment = normalize_comment(line.comment().unwrap_or_default());
+                if let Some(block) = blocks.last_mut() {
+                    block.push(Row::Fixed(comment));
+                }
+            }
+            ParsedLine::BlankLine => {}
+            ParsedLine::InvalidLine { error } => errors.push(error),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut formatted = String::new();
+    for block in blocks {
+        let (item_width, quantity_width) =
+            block
+                .iter()
+                .fold((0, 0), |(item_width, quantity_width), row| match row {
+                    Row::Stack { item, quantity, .. } => (
+                        item_width.max(item.chars().count()),
+                        quantity_width.max(quantity.len()),
+                    ),
+                    Row::Fixed(_) => (item_width, quantity_width),
+                });
+
+        for row in block {
+            match row {
+                Row::Fixed(line) => formatted.push_str(&line),
+                Row::Stack {
+                    item,
+                    quantity,
+                    comment,
+                } => {
+                    formatted.push_str(&format!(
+                        "- {:<item_width$} {:>quantity_width$}",
+                        item, quantity
+                    ));
+                    if let Some(comment) = comment {
+                        formatted.push_str("  ");
+                        formatted.push_str(&comment);
+                    }
+                }
+            }
+            formatted.push('\n');
+        }
+    }
+
+    Ok(formatted)
+}
+
+///
+/// Write the attributes of an Item that differ from the defaults, in the
+/// item file syntax.
+///
+fn item_attributes(item: &Item) -> Vec<String> {
+    let mut attributes = Vec::new();
+
+    if item.get_max_stack_size() != Item::UNLIMITED_STACK_SIZE {
+        attributes.push(format!("stack={}", item.get_max_stack_size()));
+    }
+    if item.get_weight() > 0 {
+        attributes.push(format!(
+            "weight={}",
+            items::format_kilograms(item.get_weight())
+        ));
+    }
+    if let Some(category) = item.get_category() {
+        attributes.push(format!("category={}", category));
+    }
+    if !item.get_tags().is_empty() {
+        attributes.push(format!("tags={}", item.get_tags().join(",")));
+    }
+
+    attributes
+}
+
+///
+/// Rewrite a comment with a single `// ` marker--e.g., `///Note` becomes
+/// `// Note`.
+///
+fn normalize_comment(comment: &str) -> String {
+    let text = comment.trim_start_matches('/').trim();
+
+    if text.is_empty() {
+        COMMENT_MARKER.to_owned()
+    } else {
+        format!("{} {}", COMMENT_MARKER, text)
+    }
+}
@@ -1,6 +1,107 @@
-use std::collections::LinkedList;
+use crate::error::InventoryError;
+use crate::filter::ItemFilter;
+use crate::items::{format_kilograms, ItemStack};
+use crate::slots::Slots;
 
-use crate::items::ItemStack;
+///
+/// Reason why some (or all) items could not be added to an Inventory.
+///
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RejectReason {
+    /// No empty slot was left for the items.
+    InventoryFull,
+
+    /// The Inventory does not accept this kind of Item.
+    ItemForbidden,
+
+    /// The items would push the Inventory past its weight limit.
+    WeightExceeded,
+}
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let reason = match self {
+            RejectReason::InventoryFull => "inventory full",
+            RejectReason::ItemForbidden => "item forbidden",
+            RejectReason::WeightExceeded => "weight exceeded",
+        };
+
+        write!(f, "{}", reason)
+    }
+}
+
+///
+/// Result of a call to `Inventory::add_items`.
+///
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub enum AddOutcome {
+    /// Every item was merged into stacks that were already present.
+    Merged { accepted: usize },
+
+    /// Every item was stored, and at least one new slot was opened.
+    NewSlot { accepted: usize, new_slots: usize },
+
+    /// Only some items were stored. The rest are returned in `leftover`.
+    Partial {
+        accepted: usize,
+        leftover: ItemStack,
+        reason: RejectReason,
+    },
+
+    /// Nothing was stored. The original stack is returned.
+    Rejected {
+        reason: RejectReason,
+        stack: ItemStack,
+    },
+}
+
+impl AddOutcome {
+    ///
+    /// Determine whether every item was stored.
+    ///
+    pub fn is_complete(&self) -> bool {
+        matches!(self, AddOutcome::Merged { .. } | AddOutcome::NewSlot { .. })
+    }
+
+    ///
+    /// Retrieve the number of items that were stored.
+    ///
+    pub fn accepted(&self) -> usize {
+        match self {
+            AddOutcome::Merged { accepted }
+            | AddOutcome::NewSlot { accepted, .. }
+            | AddOutcome::Partial { accepted, .. } => *accepted,
+            AddOutcome::Rejected { .. } => 0,
+        }
+    }
+
+    ///
+    /// Retrieve the items that were not stored.
+    ///
+    /// # Returns
+    ///
+    /// the leftover stack if anything was turned away and `None` otherwise
+    ///
+    pub fn leftover(&self) -> Option<&ItemStack> {
+        match self {
+            AddOutcome::Partial { leftover, .. } => Some(leftover),
+            AddOutcome::Rejected { stack, .. } => Some(stack),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Retrieve the reason why items were turned away, if any were.
+    ///
+    pub fn reason(&self) -> Option<RejectReason> {
+        match self {
+            AddOutcome::Partial { reason, .. } | AddOutcome::Rejected { reason, .. } => {
+                Some(*reason)
+            }
+            _ => None,
+        }
+    }
+}
 
 ///
 /// An Inventory is composed of n slots. Each slot may store only
@@ -10,17 +111,39 @@ use crate::items::ItemStack;
 /// stored. Individual slots may contain any number of the same
 /// Item-- if the Item is stackable.
 ///
+/// Slots are addressed by index (0 through n - 1), and any slot may be
+/// empty while a later one is filled. `{}` lists only the filled slots;
+/// the alternate form `{:#}` renders every slot, empty ones included.
+///
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::json::InventoryRecord",
+        try_from = "crate::json::InventoryRecord"
+    )
+)]
 pub struct Inventory {
     ///
     /// Individual item slots-- each ItemStack occupies one slot.
     ///
-    slots: LinkedList<ItemStack>,
+    slots: Slots,
 
     ///
     /// Total number of distinct Item types that can be stored.
     ///
     capacity: usize,
+
+    ///
+    /// Maximum combined weight (in grams) of all stored items, if limited.
+    ///
+    max_weight: Option<u64>,
+
+    ///
+    /// Decides which Items may be stored at all.
+    ///
+    filter: ItemFilter,
 }
 
 impl Default for Inventory {
@@ -45,11 +168,15 @@ impl Inventory {
     ///
     /// * `rhs` - stack whose size we need to examine
     ///
-    pub fn merge_stacks(lhs: &mut ItemStack, rhs: ItemStack) {
+    /// # Returns
+    ///
+    /// the number of items from *rhs* that did not fit in *lhs*
+    ///
+    pub fn merge_stacks(lhs: &mut ItemStack, rhs: ItemStack) -> usize {
         // lhs needs to have items added to it.
         // rhs's size is needed
         // lhs.????(rhs.????)
-        lhs.add_items(rhs.size());
+        lhs.add_items(rhs.size())
     }
 
     ///
@@ -61,8 +188,10 @@ impl Inventory {
     ///
     pub fn new(desired_capacity: usize) -> Self {
         Self {
-            slots: LinkedList::new(),
+            slots: Slots::new(),
             capacity: desired_capacity,
+            max_weight: None,
+            filter: ItemFilter::Any,
         }
     }
 
@@ -70,7 +199,7 @@ impl Inventory {
     /// Determine the number of slots currently in use.
     ///
     pub fn utilized_slots(&self) -> usize {
-        return self.slots.len();
+        self.slots.len()
     }
 
     ///
@@ -107,12 +236,12 @@ impl Inventory {
     /// true if current size is zero
     ///
     pub fn is_empty(&self) -> bool {
-        self.slots.len() == 0
+        self.slots.is_empty()
     }
 
     ///
-    /// Search through all slots (Nodes in the LinkedList) and look for a
-    /// matching ItemStack.
+    /// Look up the slots holding the key's Item and return the first
+    /// ItemStack of the same kind. Quantities are not compared.
     ///
     /// # Arguments
     ///
@@ -123,61 +252,547 @@ impl Inventory {
     /// matching stack if one was found and `null` otherwise
     ///
     pub fn find_matching_item_stack(&mut self, key: &ItemStack) -> Option<&mut ItemStack> {
-        self.slots.iter_mut().find(|stack| stack == &key)
+        let position = self
+            .slots
+            .positions_of(key.get_item().get_id())
+            .iter()
+            .copied()
+            .find(|&position| {
+                self.slots
+                    .get(position)
+                    .is_some_and(|stack| stack.is_same_kind(key))
+            })?;
+
+        self.slots.get_mut(position)
     }
 
     ///
-    /// This is the standard Linked List append operation from Review 01
+    /// Store a stack in the lowest free slot without checking capacity or
+    /// looking for a stack to merge with.
     ///
     /// # Arguments
     ///
-    /// * `to_add` - data that we want to store in a Node and add to the list
+    /// * `to_add` - stack to place in its own slot
+    ///
+    /// # Returns
+    ///
+    /// the index of the slot the stack now occupies
     ///
-    pub fn add_item_stack_no_check(&mut self, to_add: ItemStack) {
-        self.slots.push_back(to_add);
+    pub fn add_item_stack_no_check(&mut self, to_add: ItemStack) -> usize {
+        self.slots.insert(to_add)
     }
 
     ///
     /// Add one or more items to the inventory list.
     ///
+    /// Existing stacks of the same Item are filled up to the Item's maximum
+    /// stack size first. Any remainder is spilled into new slots for as
+    /// long as empty slots remain. Items that are not stackable are placed
+    /// one per slot.
+    ///
     /// # Arguments
     ///
     /// * `stack` - new stack of items to add
     ///
     /// # Returns
     ///
-    /// true if *stack* was added and false otherwise
+    /// where the items went--or, if some did not fit, why not
     ///
-    pub fn add_items(&mut self, stack: ItemStack) -> bool {
-        if let Some(ref mut the_match) = self.find_matching_item_stack(&stack) {
-            // If the Item is stackable, add it to the ItemStack
-            if the_match.permits_stacking() {
-                the_match.add_items(stack.size());
+    pub fn add_items(&mut self, stack: ItemStack) -> AddOutcome {
+        if !self.filter.accepts(stack.get_item()) {
+            return AddOutcome::Rejected {
+                reason: RejectReason::ItemForbidden,
+                stack,
+            };
+        }
+
+        // Turn away whatever would exceed the weight limit up front
+        let mut remaining = stack.size().min(self.units_within_weight_limit(&stack));
+        let overweight = stack.size() - remaining;
+
+        // Top off existing stacks of the same Item first...
+        if stack.permits_stacking() {
+            let positions = self.slots.positions_of(stack.get_item().get_id()).to_vec();
 
-                return true;
+            for position in positions {
+                if remaining == 0 {
+                    break;
+                }
+
+                if let Some(existing) = self.slots.get_mut(position) {
+                    if existing.is_same_kind(&stack) {
+                        remaining = existing.add_items(remaining);
+                    }
+                }
             }
         }
 
-        if self.utilized_slots() < self.capacity {
-            self.add_item_stack_no_check(stack);
-            return true;
+        // ...then spill whatever is left into new slots
+        let max_stack_size = stack.get_item().get_max_stack_size();
+        let mut new_slots = 0;
+        while remaining > 0 && self.utilized_slots() < self.capacity {
+            let qty = remaining.min(max_stack_size);
+            self.add_item_stack_no_check(ItemStack::new(stack.get_item().clone(), qty));
+
+            remaining -= qty;
+            new_slots += 1;
         }
 
-        return false;
+        let accepted = stack.size() - remaining - overweight;
+        let reason = if remaining > 0 {
+            RejectReason::InventoryFull
+        } else {
+            RejectReason::WeightExceeded
+        };
+        let leftover = remaining + overweight;
+
+        match (leftover, accepted, new_slots) {
+            (0, _, 0) => AddOutcome::Merged { accepted },
+            (0, _, _) => AddOutcome::NewSlot {
+                accepted,
+                new_slots,
+            },
+            (_, 0, _) => AddOutcome::Rejected { reason, stack },
+            (_, _, _) => AddOutcome::Partial {
+                accepted,
+                leftover: ItemStack::new(stack.get_item().clone(), leftover),
+                reason,
+            },
+        }
+    }
+
+    ///
+    /// Determine how many units of a stack's Item can be added before the
+    /// weight limit (if any) is exceeded.
+    ///
+    fn units_within_weight_limit(&self, stack: &ItemStack) -> usize {
+        let unit_weight = stack.get_item().get_weight();
+
+        match self.max_weight {
+            Some(max_weight) if unit_weight > 0 => {
+                let spare = max_weight.saturating_sub(self.total_weight());
+                usize::try_from(spare / unit_weight).unwrap_or(usize::MAX)
+            }
+            _ => usize::MAX,
+        }
+    }
+
+    ///
+    /// Compute the combined weight of everything stored.
+    ///
+    /// # Returns
+    ///
+    /// the weight in grams
+    ///
+    pub fn total_weight(&self) -> u64 {
+        self.iter().map(ItemStack::weight).sum()
+    }
+
+    ///
+    /// Retrieve the filter that decides which Items may be stored.
+    ///
+    pub fn get_filter(&self) -> &ItemFilter {
+        &self.filter
+    }
+
+    ///
+    /// Update the filter. Items already stored are kept even if the new
+    /// filter would reject them.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - replacement filter
+    ///
+    pub fn set_filter(&mut self, filter: ItemFilter) {
+        self.filter = filter;
+    }
+
+    ///
+    /// Retrieve the weight limit, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// the maximum total weight in grams
+    ///
+    pub fn get_max_weight(&self) -> Option<u64> {
+        self.max_weight
+    }
+
+    ///
+    /// Update the weight limit. Items already stored are kept even if they
+    /// exceed the new limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_weight` - maximum total weight in grams, or `None` for no limit
+    ///
+    pub fn set_max_weight(&mut self, max_weight: Option<u64>) {
+        self.max_weight = max_weight;
+    }
+
+    ///
+    /// Retrieve the stack in a given slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - 0-based slot index
+    ///
+    pub fn get_stack(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot)
+    }
+
+    ///
+    /// Iterate over the stored stacks in the order they were stored.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &ItemStack> {
+        self.slots.iter().map(|(_, stack)| stack)
+    }
+
+    ///
+    /// Count how many of an Item are stored across all slots.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Item id to count
+    ///
+    pub fn quantity_of(&self, id: u64) -> usize {
+        self.slots
+            .positions_of(id)
+            .iter()
+            .filter_map(|&position| self.slots.get(position))
+            .map(ItemStack::size)
+            .sum()
+    }
+
+    ///
+    /// Count how many items carrying a tag are stored across all slots.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - tag to look for (case-insensitive)
+    ///
+    pub fn quantity_tagged(&self, tag: &str) -> usize {
+        self.iter()
+            .filter(|stack| stack.get_item().has_tag(tag))
+            .map(ItemStack::size)
+            .sum()
+    }
+
+    ///
+    /// Iterate over the stacks whose Item belongs to a category.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - category to look for (case-insensitive)
+    ///
+    pub fn stacks_in_category<'a>(
+        &'a self,
+        category: &'a str,
+    ) -> impl Iterator<Item = &'a ItemStack> + 'a {
+        self.iter()
+            .filter(move |stack| stack.get_item().is_in_category(category))
+    }
+
+    ///
+    /// Take a number of items out of the inventory. Items are taken from
+    /// the highest numbered slots first, and slots that end up empty are
+    /// freed. Removing zero items does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Item id to remove
+    ///
+    /// * `qty` - number of items to remove
+    ///
+    /// # Returns
+    ///
+    /// the removed items as one stack (empty if *qty* is zero), or a
+    /// shortage error (leaving the inventory untouched) if fewer than *qty*
+    /// are stored
+    ///
+    pub fn remove_items(&mut self, id: u64, qty: usize) -> Result<ItemStack, InventoryError> {
+        if qty == 0 {
+            return Ok(ItemStack::default());
+        }
+
+        let available = self.quantity_of(id);
+        let item = self
+            .slots
+            .positions_of(id)
+            .first()
+            .and_then(|&position| self.slots.get(position))
+            .map(|stack| stack.get_item().clone());
+
+        let item = match item {
+            Some(item) if available >= qty => item,
+            _ => {
+                return Err(InventoryError::Shortage {
+                    id,
+                    requested: qty,
+                    available,
+                })
+            }
+        };
+
+        let mut remaining = qty;
+        for position in self.slots.positions_of(id).to_vec().into_iter().rev() {
+            if remaining == 0 {
+                break;
+            }
+
+            if let Some(stack) = self.slots.get_mut(position) {
+                remaining -= stack.remove_items(remaining);
+
+                if stack.size() == 0 {
+                    self.slots.take(position);
+                }
+            }
+        }
+
+        Ok(ItemStack::new(item, qty))
+    }
+
+    ///
+    /// Remove an entire stack, freeing its slot. Other stacks keep their
+    /// slot indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - 0-based slot index
+    ///
+    /// # Returns
+    ///
+    /// the removed stack or `None` if the slot is empty
+    ///
+    pub fn take_stack(&mut self, slot: usize) -> Option<ItemStack> {
+        self.slots.take(slot)
+    }
+
+    ///
+    /// Put a stack into a specific slot. Whatever occupied the slot is
+    /// swapped out.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - 0-based slot index
+    ///
+    /// * `stack` - stack to place
+    ///
+    /// # Returns
+    ///
+    /// the stack previously in the slot (if any), or an error if the slot
+    /// does not exist, the stack is empty or larger than the Item's stack
+    /// limit, the filter rejects the Item, or the weight limit would be
+    /// exceeded
+    ///
+    pub fn place_stack(
+        &mut self,
+        slot: usize,
+        stack: ItemStack,
+    ) -> Result<Option<ItemStack>, InventoryError> {
+        self.check_slot(slot)?;
+
+        let item = stack.get_item();
+        if stack.size() == 0 || stack.size() > item.get_max_stack_size() {
+            return Err(InventoryError::BadStackSize {
+                id: item.get_id(),
+                quantity: stack.size(),
+                max_stack_size: item.get_max_stack_size(),
+            });
+        }
+
+        let reason = if !self.filter.accepts(item) {
+            Some(RejectReason::ItemForbidden)
+        } else {
+            let replaced_weight = self.slots.get(slot).map_or(0, ItemStack::weight);
+            let new_total = (self.total_weight() - replaced_weight).saturating_add(stack.weight());
+
+            self.max_weight
+                .filter(|max_weight| new_total > *max_weight)
+                .map(|_| RejectReason::WeightExceeded)
+        };
+
+        if let Some(reason) = reason {
+            return Err(InventoryError::Rejected {
+                id: item.get_id(),
+                requested: stack.size(),
+                accepted: 0,
+                reason,
+            });
+        }
+
+        let previous = self.slots.take(slot);
+        // The slot was just emptied, so this cannot fail
+        let _ = self.slots.insert_at(slot, stack);
+
+        Ok(previous)
+    }
+
+    ///
+    /// Exchange the contents of two slots. Either (or both) may be empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - 0-based index of one slot
+    ///
+    /// * `second` - 0-based index of the other slot
+    ///
+    pub fn swap_slots(&mut self, first: usize, second: usize) -> Result<(), InventoryError> {
+        self.check_slot(first)?;
+        self.check_slot(second)?;
+
+        self.slots.swap(first, second);
+
+        Ok(())
+    }
+
+    ///
+    /// Iterate over every slot--empty or not--in slot order.
+    ///
+    pub fn iter_slots(&self) -> impl Iterator<Item = (usize, Option<&ItemStack>)> {
+        (0..self.capacity.max(self.slots.end())).map(|slot| (slot, self.slots.get(slot)))
+    }
+
+    ///
+    /// Make sure a slot index refers to one of this inventory's slots.
+    ///
+    fn check_slot(&self, slot: usize) -> Result<(), InventoryError> {
+        if slot < self.capacity {
+            Ok(())
+        } else {
+            Err(InventoryError::SlotOutOfRange {
+                slot,
+                total_slots: self.capacity,
+            })
+        }
+    }
+
+    ///
+    /// Remove every stack from the inventory.
+    ///
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+
+    ///
+    /// Remove every stack for which a predicate holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - returns true for stacks that should be removed
+    ///
+    /// # Returns
+    ///
+    /// the removed stacks in the order they were stored
+    ///
+    pub fn drain_matching<P>(&mut self, predicate: P) -> Vec<ItemStack>
+    where
+        P: FnMut(&ItemStack) -> bool,
+    {
+        self.slots.drain_matching(predicate)
+    }
+
+    ///
+    /// Move items of one kind into another inventory. The move either
+    /// happens in full or not at all--on error both inventories are left
+    /// untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - destination inventory
+    ///
+    /// * `id` - Item id to move
+    ///
+    /// * `qty` - number of items to move
+    ///
+    pub fn transfer_to(
+        &mut self,
+        other: &mut Inventory,
+        id: u64,
+        qty: usize,
+    ) -> Result<(), InventoryError> {
+        let moving = self.clone().remove_items(id, qty)?;
+
+        let mut destination = other.clone();
+        Self::store_all(&mut destination, moving)?;
+
+        *other = destination;
+        self.remove_items(id, qty)?;
+
+        Ok(())
+    }
+
+    ///
+    /// Move every stack into another inventory, leaving this one empty.
+    /// The merge either happens in full or not at all--on error both
+    /// inventories are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - destination inventory
+    ///
+    pub fn merge_into(&mut self, other: &mut Inventory) -> Result<(), InventoryError> {
+        let mut destination = other.clone();
+        for stack in self.iter() {
+            Self::store_all(&mut destination, stack.clone())?;
+        }
+
+        *other = destination;
+        self.clear();
+
+        Ok(())
+    }
+
+    ///
+    /// Add a stack to an inventory and treat anything short of full
+    /// acceptance as an error.
+    ///
+    fn store_all(destination: &mut Inventory, stack: ItemStack) -> Result<(), InventoryError> {
+        let outcome = destination.add_items(stack.clone());
+
+        match outcome.reason() {
+            None => Ok(()),
+            Some(reason) => Err(InventoryError::Rejected {
+                id: stack.get_item().get_id(),
+                requested: stack.size(),
+                accepted: outcome.accepted(),
+                reason,
+            }),
+        }
     }
 }
 
 impl std::fmt::Display for Inventory {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        writeln!(
+        write!(
             f,
             " -Used {} of {} slots",
             self.utilized_slots(),
             self.capacity
         )?;
+        match self.max_weight {
+            Some(max_weight) => writeln!(
+                f,
+                ", {} of {} kg",
+                format_kilograms(self.total_weight()),
+                format_kilograms(max_weight)
+            )?,
+            None => writeln!(f)?,
+        }
 
-        for stack in self.slots.iter() {
-            writeln!(f, "  {}", stack)?;
+        if f.alternate() {
+            let width = self.capacity.saturating_sub(1).to_string().len();
+
+            for (slot, stack) in self.iter_slots() {
+                match stack {
+                    Some(stack) => writeln!(f, "  [{:>width$}] {}", slot, stack)?,
+                    None => writeln!(f, "  [{:>width$}] (empty)", slot)?,
+                }
+            }
+        } else {
+            for stack in self.iter() {
+                writeln!(f, "  {}", stack)?;
+            }
         }
 
         Ok(())
@@ -6,12 +6,29 @@
 /// same numeric id.
 ///
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "crate::json::ItemRecord", try_from = "crate::json::ItemRecord")
+)]
 pub struct Item {
     /// Unique numeric id
     id: u64,
 
     /// Short title--e.g., HP Potion.
     name: String,
+
+    /// Largest number of this Item that fits in a single ItemStack.
+    max_stack_size: usize,
+
+    /// Weight of a single unit in grams.
+    weight: u64,
+
+    /// Broad classification--e.g., ore, potion, block, or tool.
+    category: Option<String>,
+
+    /// Free-form labels--e.g., fuel or magical.
+    tags: Vec<String>,
 }
 
 impl Default for Item {
@@ -21,6 +38,12 @@ impl Default for Item {
 }
 
 impl Item {
+    ///
+    /// Stack limit used when an Item does not declare one--i.e., stacks
+    /// of the Item may grow without bound.
+    ///
+    pub const UNLIMITED_STACK_SIZE: usize = usize::MAX;
+
     /// Create an Item with a specified and name.
     ///
     /// # Arguments
@@ -28,7 +51,14 @@ impl Item {
     /// * `nme` - desired name
     ///
     pub fn new(id: u64, nme: String) -> Self {
-        Self { id, name: nme }
+        Self {
+            id,
+            name: nme,
+            max_stack_size: Self::UNLIMITED_STACK_SIZE,
+            weight: 0,
+            category: None,
+            tags: Vec::new(),
+        }
     }
 
     ///
@@ -66,6 +96,130 @@ impl Item {
     pub fn set_name(&mut self, nme: String) {
         self.name = nme
     }
+
+    ///
+    /// Retrieve the maximum number of items per stack.
+    ///
+    pub fn get_max_stack_size(&self) -> usize {
+        self.max_stack_size
+    }
+
+    ///
+    /// Update the maximum number of items per stack.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - replacement limit (values below 1 are treated as 1)
+    ///
+    pub fn set_max_stack_size(&mut self, max: usize) {
+        self.max_stack_size = max.max(1);
+    }
+
+    ///
+    /// Retrieve the weight of one unit in grams.
+    ///
+    pub fn get_weight(&self) -> u64 {
+        self.weight
+    }
+
+    ///
+    /// Update the weight of one unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `grams` - replacement weight in grams
+    ///
+    pub fn set_weight(&mut self, grams: u64) {
+        self.weight = grams;
+    }
+
+    ///
+    /// Retrieve the category, if one was assigned.
+    ///
+    pub fn get_category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    ///
+    /// Update the category. Categories are compared case-insensitively and
+    /// stored in lowercase.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - replacement category, or `None` to clear it
+    ///
+    pub fn set_category(&mut self, category: Option<&str>) {
+        self.category = category.map(str::to_lowercase);
+    }
+
+    ///
+    /// Retrieve all tags (in lowercase, in the order they were added).
+    ///
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    ///
+    /// Add a tag. Tags are compared case-insensitively, and adding a tag
+    /// that is already present has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - tag to add
+    ///
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.to_lowercase();
+
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    ///
+    /// Determine whether this Item carries a tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - tag to look for (case-insensitive)
+    ///
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag.to_lowercase())
+    }
+
+    ///
+    /// Determine whether this Item belongs to a category.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - category to check (case-insensitive)
+    ///
+    pub fn is_in_category(&self, category: &str) -> bool {
+        self.category.as_deref() == Some(category.to_lowercase().as_str())
+    }
+
+    ///
+    /// Determine whether more than one of this Item may share a stack.
+    /// Tools, armor, and other unique items are not stackable.
+    ///
+    pub fn is_stackable(&self) -> bool {
+        self.max_stack_size > 1
+    }
+
+    ///
+    /// Update stackability.
+    ///
+    /// # Arguments
+    ///
+    /// * `stackable` - false limits stacks to a single item; true lifts
+    ///   that limit (an existing limit above one is kept)
+    ///
+    pub fn set_stackable(&mut self, stackable: bool) {
+        if !stackable {
+            self.max_stack_size = 1;
+        } else if !self.is_stackable() {
+            self.max_stack_size = Self::UNLIMITED_STACK_SIZE;
+        }
+    }
 }
 
 impl std::fmt::Display for Item {
@@ -76,10 +230,49 @@ impl std::fmt::Display for Item {
     }
 }
 
+///
+/// Convert a decimal number of kilograms--e.g., `2.5`--to grams.
+///
+/// # Returns
+///
+/// the weight in grams, or `None` if the text is not a non-negative
+/// decimal with at most three fractional digits
+///
+pub fn parse_kilograms(text: &str) -> Option<u64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+    let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !all_digits(whole) || fraction.len() > 3 || !all_digits(fraction) {
+        return None;
+    }
+
+    let whole: u64 = whole.parse().ok()?;
+    let fraction: u64 = format!("{:0<3}", fraction).parse().ok()?;
+
+    whole.checked_mul(1000)?.checked_add(fraction)
+}
+
+///
+/// Render a weight in grams as kilograms, without trailing zeros--e.g.,
+/// 2500 becomes `2.5`.
+///
+pub fn format_kilograms(grams: u64) -> String {
+    let (whole, fraction) = (grams / 1000, grams % 1000);
+
+    if fraction == 0 {
+        whole.to_string()
+    } else {
+        format!("{}.{:03}", whole, fraction)
+            .trim_end_matches('0')
+            .to_string()
+    }
+}
+
 ///
 /// A Homogeneous--i.e., uniform--stack of Items.
 ///
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ItemStack {
     ///
     /// The specific type of item out of which this stack is built.
@@ -129,6 +322,22 @@ impl ItemStack {
         &self.item
     }
 
+    ///
+    /// Determine whether two stacks hold the same kind of Item--i.e., equal
+    /// Items (same id and same Item data)--regardless of how many items
+    /// each holds.
+    ///
+    /// Use `==` when full structural equality (including quantity) is
+    /// needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - stack to compare against
+    ///
+    pub fn is_same_kind(&self, other: &ItemStack) -> bool {
+        self.item == other.item
+    }
+
     ///
     /// Retrieve the size of the stack.
     ///
@@ -141,14 +350,65 @@ impl ItemStack {
     }
 
     ///
-    /// Increase the size of the stack.
+    /// Compute the combined weight of all items in the stack.
+    ///
+    /// # Returns
+    ///
+    /// the weight in grams
+    ///
+    pub fn weight(&self) -> u64 {
+        self.item.get_weight().saturating_mul(self.quantity as u64)
+    }
+
+    ///
+    /// Determine how many more items fit before the stack reaches the
+    /// Item's maximum stack size.
+    ///
+    pub fn space_remaining(&self) -> usize {
+        self.item.get_max_stack_size().saturating_sub(self.quantity)
+    }
+
+    ///
+    /// Determine whether the stack has reached its maximum size.
+    ///
+    pub fn is_full(&self) -> bool {
+        self.space_remaining() == 0
+    }
+
+    ///
+    /// Increase the size of the stack, up to the Item's maximum stack size.
     ///
     /// # Arguments
     ///
     /// * `qty` - number of items to add
     ///
-    pub fn add_items(&mut self, qty: usize) {
-        self.quantity += qty;
+    /// # Returns
+    ///
+    /// the number of items that did not fit
+    ///
+    pub fn add_items(&mut self, qty: usize) -> usize {
+        let accepted = qty.min(self.space_remaining());
+        self.quantity += accepted;
+
+        qty - accepted
+    }
+
+    ///
+    /// Decrease the size of the stack.
+    ///
+    /// # Arguments
+    ///
+    /// * `qty` - number of items to remove
+    ///
+    /// # Returns
+    ///
+    /// the number of items actually removed--never more than the stack held
+    ///
+    pub fn remove_items(&mut self, qty: usize) -> usize {
+        let removed = qty.min(self.quantity);
+        self.quantity -= removed;
+
+        removed
     }
 
     ///
@@ -161,8 +421,7 @@ impl ItemStack {
     /// true if the addition of items is permitted
     ///
     pub fn permits_stacking(&self) -> bool {
-        // For now... all items are stackable
-        return true;
+        self.item.is_stackable()
     }
 }
 
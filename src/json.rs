@@ -0,0 +1,242 @@
+//! JSON import and export (requires the `serde` feature).
+//!
+//! # Item catalog
+//!
+//! A catalog is an array of items. Only `id` and `name` are required;
+//! the other fields take the same defaults as in the item file.
+//!
+//! ```json
+//! [
+//!   { "id": 1, "name": "HP Potion", "max_stack_size": 16 },
+//!   { "id": 5, "name": "Iron Ore", "weight_grams": 2500,
+//!     "category": "ore", "tags": ["smeltable"] }
+//! ]
+//! ```
+//!
+//!  * `max_stack_size` - at least 1; omitted means unlimited
+//!  * `weight_grams` - weight of one unit; omitted means 0
+//!  * `category` - omitted means no category
+//!  * `tags` - omitted means no tags
+//!
+//! Ids and names (ignoring case) must be unique, as in `ItemCatalog`.
+//!
+//! # Inventories
+//!
+//! A list of inventories is an array of objects, each holding its
+//! capacity, optional limits, and the occupied slots. Every slot embeds
+//! the full item so that a document can be read without a catalog.
+//!
+//! ```json
+//! [
+//!   {
+//!     "capacity": 4,
+//!     "max_weight_grams": 20000,
+//!     "accept": "1-9,category:ore",
+//!     "slots": [
+//!       { "slot": 0, "item": { "id": 1, "name": "HP Potion" }, "quantity": 10 }
+//!     ]
+//!   }
+//! ]
+//! ```
+//!
+//!  * `max_weight_grams` - omitted means no weight limit
+//!  * `accept` - an `ItemFilter` in its text form; omitted means any item
+//!  * `slots` - each `slot` must be below `capacity` and listed once,
+//!    each item must pass the filter, and each `quantity` must be between
+//!    1 and the item's `max_stack_size`
+//!
+//! The slots together must also stay within `max_weight_grams`.
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::filter::ItemFilter;
+use crate::inventory::Inventory;
+use crate::items::{Item, ItemStack};
+
+///
+/// JSON form of an Item.
+///
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ItemRecord {
+    id: u64,
+    name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_stack_size: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_zero")]
+    weight_grams: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+impl From<Item> for ItemRecord {
+    fn from(item: Item) -> Self {
+        let max_stack_size = item.get_max_stack_size();
+
+        ItemRecord {
+            id: item.get_id(),
+            name: item.get_name().to_owned(),
+            max_stack_size: (max_stack_size != Item::UNLIMITED_STACK_SIZE)
+                .then_some(max_stack_size),
+            weight_grams: item.get_weight(),
+            category: item.get_category().map(str::to_owned),
+            tags: item.get_tags().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<ItemRecord> for Item {
+    type Error = String;
+
+    fn try_from(record: ItemRecord) -> Result<Self, Self::Error> {
+        let mut item = Item::new(record.id, record.name);
+
+        match record.max_stack_size {
+            Some(0) => {
+                return Err(format!(
+                    "item {}: max_stack_size must be at least 1",
+                    record.id
+                ))
+            }
+            Some(limit) => item.set_max_stack_size(limit),
+            None => {}
+        }
+
+        item.set_weight(record.weight_grams);
+        item.set_category(record.category.as_deref());
+        for tag in record.tags.iter() {
+            item.add_tag(tag);
+        }
+
+        Ok(item)
+    }
+}
+
+///
+/// JSON form of one occupied Inventory slot.
+///
+#[derive(Serialize, Deserialize)]
+struct SlotRecord {
+    slot: usize,
+    item: Item,
+    quantity: usize,
+}
+
+///
+/// JSON form of an Inventory.
+///
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InventoryRecord {
+    capacity: usize,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_weight_grams: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "accepts_anything")]
+    accept: ItemFilter,
+
+    slots: Vec<SlotRecord>,
+}
+
+impl From<Inventory> for InventoryRecord {
+    fn from(inventory: Inventory) -> Self {
+        let slots = inventory
+            .iter_slots()
+            .filter_map(|(slot, stack)| {
+                stack.map(|stack| SlotRecord {
+                    slot,
+                    item: stack.get_item().clone(),
+                    quantity: stack.size(),
+                })
+            })
+            .collect();
+
+        InventoryRecord {
+            capacity: inventory.total_slots(),
+            max_weight_grams: inventory.get_max_weight(),
+            accept: inventory.get_filter().clone(),
+            slots,
+        }
+    }
+}
+
+impl TryFrom<InventoryRecord> for Inventory {
+    type Error = String;
+
+    fn try_from(record: InventoryRecord) -> Result<Self, Self::Error> {
+        let mut inventory = Inventory::new(record.capacity);
+        inventory.set_max_weight(record.max_weight_grams);
+        inventory.set_filter(record.accept);
+
+        for slot in record.slots {
+            let stack = ItemStack::new(slot.item, slot.quantity);
+
+            match inventory.place_stack(slot.slot, stack) {
+                Ok(None) => {}
+                Ok(Some(_)) => return Err(format!("slot {} is listed more than once", slot.slot)),
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+
+        Ok(inventory)
+    }
+}
+
+impl From<ItemFilter> for String {
+    fn from(filter: ItemFilter) -> Self {
+        filter.to_string()
+    }
+}
+
+impl TryFrom<String> for ItemFilter {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        if text.is_empty() {
+            return Ok(ItemFilter::Any);
+        }
+
+        ItemFilter::parse(&text).ok_or_else(|| format!("malformed item filter `{}`", text))
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn accepts_anything(filter: &ItemFilter) -> bool {
+    *filter == ItemFilter::Any
+}
+
+///
+/// Write an item catalog as pretty-printed JSON.
+///
+/// # Arguments
+///
+///  * `outs` - output destination
+///  * `catalog` - Items to write, in catalog order
+///
+pub fn write_catalog<W: Write>(
+    outs: W,
+    catalog: &crate::catalog::ItemCatalog,
+) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(outs, catalog)
+}
+
+///
+/// Write a list of inventories as pretty-printed JSON.
+///
+/// # Arguments
+///
+///  * `outs` - output destination
+///  * `inventories` - inventories to write, in order
+///
+pub fn write_inventories<W: Write>(outs: W, inventories: &[Inventory]) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(outs, inventories)
+}
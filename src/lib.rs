@@ -1,9 +1,20 @@
+pub mod catalog;
+pub mod cst;
+pub mod csv;
 pub mod error;
+pub mod filter;
+pub mod formatter;
 pub mod inventory;
 pub mod items;
+#[cfg(feature = "serde")]
+pub mod json;
 pub mod parser;
+pub mod processing;
+pub mod slots;
+pub mod writer;
 
 pub mod prelude {
+    pub use crate::catalog::ItemCatalog;
     pub use crate::inventory::Inventory;
     pub use crate::items::Item;
     pub use crate::items::ItemStack;
@@ -1,102 +1,277 @@
-use eyre::WrapErr;
-
-use rust_inventory::parser::ParsedLine;
+use rust_inventory::csv;
+use rust_inventory::error::ParseErrors;
+use rust_inventory::formatter::{self, FileKind};
 use rust_inventory::prelude::*;
+use rust_inventory::processing::{self, ProcessingReport};
+use rust_inventory::writer::InventoryWriter;
+
+const USAGE: &str =
+    "[--strict] [--format text|json|inventory|csv] items_filename inventories_filename";
+
+///
+/// How results are printed.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    /// Processing log, item list, and storage summary
+    Text,
+
+    /// The resulting inventories as a JSON document
+    Json,
+
+    /// The resulting inventories in the inventory file format
+    Inventory,
+
+    /// One CSV row per occupied slot of the resulting inventories
+    Csv,
+}
+
+///
+/// Settings taken from the command line.
+///
+struct Options {
+    /// Unknown items are an error rather than a log entry
+    strict: bool,
+
+    format: OutputFormat,
+    items_filename: String,
+    inventories_filename: String,
+}
+
+impl Options {
+    fn from_args(argv: &[String]) -> eyre::Result<Self> {
+        let mut strict = false;
+        let mut format = OutputFormat::Text;
+        let mut filenames = Vec::new();
+
+        let mut args = argv.iter().skip(1);
+        while let Some(arg) = args.next() {
+            let format_name = match arg.as_str() {
+                "--strict" => {
+                    strict = true;
+                    continue;
+                }
+                "--format" => args.next().map(String::as_str),
+                _ => arg.strip_prefix("--format="),
+            };
+
+            match (format_name, arg.starts_with("--")) {
+                (Some("text"), _) => format = OutputFormat::Text,
+                (Some("json"), _) => format = OutputFormat::Json,
+                (Some("inventory"), _) => format = OutputFormat::Inventory,
+                (Some("csv"), _) => format = OutputFormat::Csv,
+                (Some(other), _) => eyre::bail!("Unknown output format `{}`", other),
+                (None, true) => eyre::bail!("Usage: {} {}", argv[0], USAGE),
+                (None, false) => filenames.push(arg.clone()),
+            }
+        }
+
+        let [items_filename, inventories_filename] = <[String; 2]>::try_from(filenames)
+            .map_err(|_| eyre::eyre!("Usage: {} {}", argv[0], USAGE))?;
+
+        Ok(Options {
+            strict,
+            format,
+            items_filename,
+            inventories_filename,
+        })
+    }
+}
 
 fn main() -> eyre::Result<()> {
     let argv: Vec<String> = std::env::args().collect();
 
-    if argv.len() < 3 {
-        eyre::bail!("Usage: {} items_filename inventories_filename", argv[0]);
+    if argv.get(1).map(String::as_str) == Some("fmt") {
+        return run_fmt(&argv);
+    }
+
+    let options = Options::from_args(&argv)?;
+
+    let (catalog, item_errors) = if is_json(&options.items_filename) {
+        (
+            json_io::read_catalog(&options.items_filename)?,
+            ParseErrors::new(),
+        )
+    } else if is_csv(&options.items_filename) {
+        Parser::read_from_file(&options.items_filename, Parser::read_catalog_csv)?
+    } else {
+        Parser::read_from_file(&options.items_filename, Parser::read_catalog_with_errors)?
+    };
+    eprint!("{}", item_errors.with_file(&options.items_filename));
+
+    let report = if is_json(&options.inventories_filename) {
+        ProcessingReport {
+            inventories: json_io::read_inventories(&options.inventories_filename)?,
+            log: Vec::new(),
+        }
+    } else {
+        let (all_inventory_lines, inventory_errors) =
+            Parser::read_from_file(&options.inventories_filename, |ins| {
+                Parser::read_inventory_lines_with_catalog(ins, &catalog)
+            })?;
+        eprint!(
+            "{}",
+            inventory_errors.with_file(&options.inventories_filename)
+        );
+
+        processing::process_inventory_requests(&all_inventory_lines, &catalog)
+    };
+
+    match options.format {
+        OutputFormat::Text => print_report(&report, &catalog),
+        OutputFormat::Json => json_io::write_inventories(&report.inventories)?,
+        OutputFormat::Inventory => {
+            let mut writer = InventoryWriter::new();
+            writer.set_comments(true);
+            writer.write(std::io::stdout().lock(), &report.inventories)?;
+        }
+        OutputFormat::Csv => {
+            csv::write_inventory_stacks(std::io::stdout().lock(), &report.inventories)?
+        }
+    }
+
+    let unknown_items = report.unknown_items();
+    if options.strict && unknown_items > 0 {
+        eyre::bail!("{} line(s) referenced unknown items", unknown_items);
+    }
+
+    Ok(())
+}
+
+///
+/// Carry out `fmt [--check] FILE...`, which rewrites item and inventory
+/// files in canonical form. With `--check`, files are left alone and the
+/// command fails if any of them would change.
+///
+fn run_fmt(argv: &[String]) -> eyre::Result<()> {
+    let check = argv[2..].iter().any(|arg| arg == "--check");
+    let filenames: Vec<&String> = argv[2..].iter().filter(|arg| *arg != "--check").collect();
+
+    if filenames.is_empty() || filenames.iter().any(|name| name.starts_with("--")) {
+        eyre::bail!("Usage: {} fmt [--check] FILE...", argv[0]);
+    }
+
+    let mut failures = 0;
+    for filename in filenames {
+        let original = std::fs::read_to_string(filename)?;
+
+        let Some(kind) = FileKind::detect(&original) else {
+            eprintln!(
+                "{}: cannot tell whether this is an item or inventory file",
+                filename
+            );
+            failures += 1;
+            continue;
+        };
+
+        let formatted = match formatter::format(kind, &original) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                eprint!("{}", errors.with_file(filename));
+                failures += 1;
+                continue;
+            }
+        };
+
+        if formatted == original {
+            continue;
+        }
+
+        if check {
+            println!("{} is not formatted", filename);
+            failures += 1;
+        } else {
+            std::fs::write(filename, formatted)?;
+            println!("Formatted {}", filename);
+        }
     }
 
-    let all_items = Parser::read_from_file(&argv[1], |ins| Parser::read_items(ins))?;
-    let all_inventory_lines =
-        Parser::read_from_file(&argv[2], |ins| Parser::read_inventory_lines(ins))?;
+    if failures > 0 {
+        eyre::bail!("{} file(s) failed formatting", failures);
+    }
 
-    let logged_inventories = process_inventory_requests(all_inventory_lines, &all_items);
+    Ok(())
+}
+
+fn is_json(filename: &str) -> bool {
+    filename.ends_with(".json")
+}
 
+fn is_csv(filename: &str) -> bool {
+    filename.ends_with(".csv")
+}
+
+///
+/// Print the processing log, item list, and storage summary.
+///
+fn print_report(report: &ProcessingReport, catalog: &ItemCatalog) {
     println!("Processing Log:");
-    for (entries, _) in logged_inventories.iter() {
-        for entry in entries.iter() {
-            println!("{}", entry);
+    for entry in report.log.iter() {
+        if entry.event.is_item_event() {
+            println!("{}", entry.event);
         }
     }
+
+    let unknown_items = report.unknown_items();
+    if unknown_items > 0 {
+        println!("Unknown items: {}", unknown_items);
+    }
     println!();
 
     println!("Item List:");
-    for item in all_items.iter() {
+    for item in catalog.iter() {
         println!("  {:>2} {}", item.get_id(), item.get_name());
     }
     println!();
 
     println!("Storage Summary:");
-    for (_, inv) in logged_inventories.iter() {
+    for inv in report.inventories.iter() {
         println!("{}", inv);
     }
+}
 
-    Ok(())
+#[cfg(feature = "serde")]
+mod json_io {
+    use rust_inventory::json;
+    use rust_inventory::prelude::*;
+
+    pub fn read_catalog(filename: &str) -> eyre::Result<ItemCatalog> {
+        Ok(Parser::read_from_file(
+            filename,
+            Parser::read_catalog_json,
+        )??)
+    }
+
+    pub fn read_inventories(filename: &str) -> eyre::Result<Vec<Inventory>> {
+        Ok(Parser::read_from_file(
+            filename,
+            Parser::read_inventories_json,
+        )??)
+    }
+
+    pub fn write_inventories(inventories: &[Inventory]) -> eyre::Result<()> {
+        json::write_inventories(std::io::stdout().lock(), inventories)?;
+        println!();
+
+        Ok(())
+    }
 }
 
-pub fn process_inventory_requests(
-    all_inventory_lines: Vec<ParsedLine>,
-    known_items: &[Item],
-) -> Vec<(Vec<String>, Inventory)> {
-    let lines = all_inventory_lines.split(|line| match line {
-        ParsedLine::InventoryLine { .. } => true,
-        _ => false,
-    });
-
-    let inventories: Vec<Inventory> = all_inventory_lines
-        .iter()
-        .flat_map(|line| match line {
-            ParsedLine::InventoryLine { max_size } => Some(Inventory::new(*max_size)),
-            _ => None,
-        })
-        .collect();
-
-    let logged_inventories: Vec<(_, Inventory)> = inventories
-        .into_iter()
-        .zip(lines.skip(1))
-        .map(|(mut inv, entries)| {
-            let stacks_to_store: Vec<ItemStack> = entries
-                .iter()
-                .flat_map(|line| {
-                    let possible_stack = match line {
-                        ParsedLine::ItemStackLine { id, quantity } => {
-                            match known_items
-                                .iter()
-                                .find(|known_item| known_item.get_id() == *id)
-                            {
-                                Some(&ref item) => Some(ItemStack::new(item.clone(), *quantity)),
-                                None => None,
-                            }
-                        }
-                        _ => None,
-                    };
-                    possible_stack
-                })
-                .collect();
-
-            let entries: Vec<String> = stacks_to_store
-                .into_iter()
-                .map(|stack| {
-                    format!(
-                        "{:9} ({:>2}) {}",
-                        if inv.add_items(stack.clone()) {
-                            "Stored"
-                        } else {
-                            "Discarded"
-                        },
-                        stack.size(),
-                        stack.get_item().get_name()
-                    )
-                })
-                .collect();
-
-            (entries, inv)
-        })
-        .collect();
+#[cfg(not(feature = "serde"))]
+mod json_io {
+    use rust_inventory::prelude::*;
+
+    const UNSUPPORTED: &str = "JSON support requires building with `--features serde`";
+
+    pub fn read_catalog(_filename: &str) -> eyre::Result<ItemCatalog> {
+        eyre::bail!(UNSUPPORTED)
+    }
+
+    pub fn read_inventories(_filename: &str) -> eyre::Result<Vec<Inventory>> {
+        eyre::bail!(UNSUPPORTED)
+    }
 
-    logged_inventories
+    pub fn write_inventories(_inventories: &[Inventory]) -> eyre::Result<()> {
+        eyre::bail!(UNSUPPORTED)
+    }
 }
@@ -1,19 +1,124 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::ops::Range;
 
+use crate::catalog::{ItemCatalog, ItemRef};
+use crate::csv;
 use crate::error::*;
-use crate::inventory::Inventory;
-use crate::items::{Item, ItemStack};
+use crate::filter::ItemFilter;
+use crate::items::{self, Item};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParsedLine {
-    ItemStackLine { id: u64, quantity: usize },
-    InventoryLine { max_size: usize },
-    CommentLine { raw_line: String },
-    InvalidLine { raw_line: String },
+    ItemStackLine {
+        item: ItemRef,
+        quantity: usize,
+    },
+    InventoryLine {
+        max_size: usize,
+        max_weight: Option<u64>,
+        filter: ItemFilter,
+    },
+    CommentLine {
+        raw_line: String,
+    },
+    BlankLine,
+    InvalidLine {
+        error: ParseError,
+    },
 }
 
+impl ParsedLine {
+    ///
+    /// Retrieve the error for an invalid line.
+    ///
+    /// # Returns
+    ///
+    /// the error if this line could not be parsed and `None` otherwise
+    ///
+    pub fn error(&self) -> Option<&ParseError> {
+        match self {
+            ParsedLine::InvalidLine { error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Split a line on whitespace, keeping track of where each token starts.
+///
+/// # Returns
+///
+/// the byte range and text of each token
+///
+fn tokenize(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (offset, ch) in line.char_indices() {
+        match (ch.is_whitespace(), start) {
+            (true, Some(token_start)) => {
+                tokens.push((token_start..offset, &line[token_start..offset]));
+                start = None;
+            }
+            (false, None) => start = Some(offset),
+            _ => {}
+        }
+    }
+
+    if let Some(token_start) = start {
+        tokens.push((token_start..line.len(), &line[token_start..]));
+    }
+
+    tokens
+}
+
+///
+/// Split input into lines without giving up on bytes that are not valid
+/// UTF-8. Reading stops at the first I/O error.
+///
+/// # Returns
+///
+/// each line (without its line terminator) as `Ok` if it was valid UTF-8,
+/// or as a lossy `Err` copy otherwise
+///
+pub(crate) fn split_lines<B: BufRead>(ins: B) -> impl Iterator<Item = Result<String, String>> {
+    ins.split(b'\n').map_while(Result::ok).map(|mut bytes| {
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+
+        String::from_utf8(bytes).map_err(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+    })
+}
+
+///
+/// What went wrong on a line and which bytes of it are to blame.
+///
+pub(crate) type LineError = (ParseErrorKind, Range<usize>);
+
+///
+/// Where an item's id and name appear on its line.
+///
+pub(crate) type ItemSpans = (Range<usize>, Range<usize>);
+
+///
+/// Lines (or tokens) starting with this marker are comments.
+///
+pub(crate) const COMMENT_MARKER: &str = "//";
+
+///
+/// Separates the key from the value in item attributes--e.g., `stack=16`.
+///
+pub(crate) const ATTRIBUTE_SEPARATOR: char = '=';
+
+///
+/// Attribute keys accepted in item files (and as CSV columns).
+///
+pub(crate) const ITEM_ATTRIBUTES: [&str; 5] = ["stack", "stackable", "weight", "category", "tags"];
+
 pub struct Parser;
 
 impl Parser {
@@ -34,50 +139,557 @@ impl Parser {
         Ok(all_things)
     }
 
+    /// Read an ItemCatalog from JSON. The `json` module documents the
+    /// expected schema.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    #[cfg(feature = "serde")]
+    pub fn read_catalog_json<R: std::io::Read>(ins: R) -> Result<ItemCatalog, ParserError> {
+        Ok(serde_json::from_reader(ins)?)
+    }
+
+    /// Read a list of inventories from JSON. The `json` module documents
+    /// the expected schema.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    #[cfg(feature = "serde")]
+    pub fn read_inventories_json<R: std::io::Read>(
+        ins: R,
+    ) -> Result<Vec<crate::inventory::Inventory>, ParserError> {
+        Ok(serde_json::from_reader(ins)?)
+    }
+
     /// Read Items from an input buffer.
     ///
+    /// Malformed lines are skipped. Use `read_items_with_errors` to find
+    /// out which lines were rejected and why.
+    ///
     /// # Arguments
     ///
     ///  * `ins` - input source
     ///
     pub fn read_items<B: BufRead>(ins: B) -> Vec<Item> {
-        Vec::new()
+        let (items, _) = Self::read_items_with_errors(ins);
+
+        items
     }
 
-    /// Read inventories from an input buffer.
+    /// Read Items from an input buffer, collecting a diagnostic for every
+    /// line that could not be used.
+    ///
+    /// Each line has the form `<id> <name> [key=value...]`, where the name
+    /// may span multiple words. Blank lines and `//` comments are ignored.
+    ///
+    /// Recognized attributes are:
+    ///
+    ///  * `stack=<n>` - maximum number of the item per stack
+    ///  * `stackable=<yes|no>` - whether the item may stack at all
+    ///  * `weight=<kg>` - weight of one unit in kilograms (e.g., `0.25`)
+    ///  * `category=<name>` - broad classification (e.g., `ore`)
+    ///  * `tags=<tag,...>` - comma-separated labels (e.g., `fuel,rare`)
     ///
     /// # Arguments
     ///
     ///  * `ins` - input source
     ///
-    pub fn read_inventory_lines<B: BufRead>(ins: B) -> Vec<ParsedLine> {
-        ins.lines()
-            .flatten()
-            .map(|line| -> Vec<String> {
-                line.trim().split_whitespace().map(String::from).collect()
-            })
-            .map(|tokens| match tokens[0].as_ref() {
-                "#" => match tokens[1].parse() {
-                    Ok(max_size) => ParsedLine::InventoryLine { max_size },
-                    Err(_) => ParsedLine::InvalidLine {
-                        raw_line: tokens.join(" "),
+    /// # Returns
+    ///
+    /// the successfully parsed items (in file order) and the errors
+    /// for all rejected lines
+    ///
+    pub fn read_items_with_errors<B: BufRead>(ins: B) -> (Vec<Item>, ParseErrors) {
+        let (catalog, errors) = Self::read_catalog_with_errors(ins);
+
+        (catalog.into_items(), errors)
+    }
+
+    /// Read an ItemCatalog from an input buffer, collecting a diagnostic
+    /// for every line that could not be used.
+    ///
+    /// Lines follow the same syntax as for `read_items_with_errors`. A line
+    /// whose id or name (ignoring case) was already used by an earlier line
+    /// is rejected.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    /// # Returns
+    ///
+    /// the catalog of successfully parsed items and the errors for all
+    /// rejected lines
+    ///
+    pub fn read_catalog_with_errors<B: BufRead>(ins: B) -> (ItemCatalog, ParseErrors) {
+        let mut catalog = ItemCatalog::new();
+        let mut errors = ParseErrors::new();
+        let mut first_seen: HashMap<u64, usize> = HashMap::new();
+
+        for (index, line) in split_lines(ins).enumerate() {
+            let line_number = index + 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(lossy_line) => {
+                    errors.push(ParseError::new(
+                        ParseErrorKind::InvalidEncoding,
+                        line_number,
+                        0..lossy_line.len(),
+                        &lossy_line,
+                    ));
+                    continue;
+                }
+            };
+            let tokens = tokenize(&line);
+
+            let (id_span, raw_id) = match tokens.first() {
+                Some((span, raw_id)) if !raw_id.starts_with(COMMENT_MARKER) => {
+                    (span.clone(), *raw_id)
+                }
+                _ => continue,
+            };
+
+            let id: u64 = match raw_id.parse() {
+                Ok(id) => id,
+                Err(_) => {
+                    errors.push(ParseError::new(
+                        ParseErrorKind::BadNumber,
+                        line_number,
+                        id_span,
+                        &line,
+                    ));
+                    continue;
+                }
+            };
+
+            // The name runs until the first `key=value` attribute
+            let name_end = tokens[1..]
+                .iter()
+                .position(|(_, token)| token.contains(ATTRIBUTE_SEPARATOR))
+                .map_or(tokens.len(), |offset| offset + 1);
+
+            let name = tokens[1..name_end]
+                .iter()
+                .map(|(_, token)| *token)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if name.is_empty() {
+                errors.push(ParseError::new(
+                    ParseErrorKind::MissingToken {
+                        expected: "item name",
                     },
+                    line_number,
+                    line.len()..line.len(),
+                    &line,
+                ));
+                continue;
+            }
+
+            let mut item = Item::new(id, name);
+            let attribute_error = tokens[name_end..].iter().find_map(|(span, token)| {
+                Self::apply_item_attribute(&mut item, span.clone(), token)
+                    .err()
+                    .map(|(kind, span)| ParseError::new(kind, line_number, span, &line))
+            });
+            if let Some(error) = attribute_error {
+                errors.push(error);
+                continue;
+            }
+
+            let name_span = tokens[1].0.start..tokens[name_end - 1].0.end;
+            if let Err((kind, span)) = Self::insert_into_catalog(
+                &mut catalog,
+                &mut first_seen,
+                item,
+                line_number,
+                (id_span, name_span),
+            ) {
+                errors.push(ParseError::new(kind, line_number, span, &line));
+            }
+        }
+
+        (catalog, errors)
+    }
+
+    ///
+    /// Read an ItemCatalog from CSV, collecting a diagnostic for every
+    /// row that could not be used. The `csv` module documents the expected
+    /// columns.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    /// # Returns
+    ///
+    /// the catalog of successfully parsed items and the errors for all
+    /// rejected rows
+    ///
+    pub fn read_catalog_csv<B: BufRead>(ins: B) -> (ItemCatalog, ParseErrors) {
+        csv::read_catalog(ins)
+    }
+
+    ///
+    /// Add a freshly parsed Item to a catalog, turning a conflict with an
+    /// earlier line into a diagnostic.
+    ///
+    /// # Arguments
+    ///
+    ///  * `first_seen` - line on which each id in the catalog was read
+    ///  * `spans` - where the item's id and name appear on its line
+    ///
+    pub(crate) fn insert_into_catalog(
+        catalog: &mut ItemCatalog,
+        first_seen: &mut HashMap<u64, usize>,
+        item: Item,
+        line_number: usize,
+        spans: ItemSpans,
+    ) -> Result<(), LineError> {
+        let id = item.get_id();
+        let (id_span, name_span) = spans;
+
+        match catalog.insert(item) {
+            Ok(()) => {
+                first_seen.insert(id, line_number);
+                Ok(())
+            }
+            Err(CatalogError::DuplicateId { .. }) => Err((
+                ParseErrorKind::DuplicateId {
+                    first_line: first_seen[&id],
                 },
-                "-" => match (tokens[1].parse(), tokens[2].parse()) {
-                    (Ok(id), Ok(quantity)) => ParsedLine::ItemStackLine { id, quantity },
-                    _ => ParsedLine::InvalidLine {
-                        raw_line: tokens.join(" "),
-                    },
+                id_span,
+            )),
+            Err(CatalogError::DuplicateName { existing, .. }) => Err((
+                ParseErrorKind::DuplicateName {
+                    first_line: first_seen[&existing],
                 },
-                "//" => {
-                    let line = tokens.join(" ");
-                    ParsedLine::CommentLine { raw_line: line }
+                name_span,
+            )),
+        }
+    }
+
+    /// Apply one `key=value` attribute from an item list line.
+    ///
+    /// # Arguments
+    ///
+    ///  * `item` - item being built
+    ///  * `span` - location of the attribute token within its line
+    ///  * `token` - the attribute token itself
+    ///
+    /// # Returns
+    ///
+    /// the kind and location of the problem if the attribute was rejected
+    ///
+    fn apply_item_attribute(
+        item: &mut Item,
+        span: Range<usize>,
+        token: &str,
+    ) -> Result<(), LineError> {
+        let Some((key, value)) = token.split_once(ATTRIBUTE_SEPARATOR) else {
+            return Err((ParseErrorKind::UnknownAttribute, span));
+        };
+        let key_span = span.start..span.start + key.len();
+        let value_span = (key_span.end + 1)..span.end;
+
+        Self::set_item_attribute(item, key, value, value_span).map_err(|(kind, span)| match kind {
+            ParseErrorKind::UnknownAttribute => (kind, key_span),
+            _ => (kind, span),
+        })
+    }
+
+    ///
+    /// Set one attribute on an Item. An unrecognized key is reported as an
+    /// `UnknownAttribute` at `value_span`.
+    ///
+    /// # Arguments
+    ///
+    ///  * `value_span` - where `value` appears on its line
+    ///
+    pub(crate) fn set_item_attribute(
+        item: &mut Item,
+        key: &str,
+        value: &str,
+        value_span: Range<usize>,
+    ) -> Result<(), LineError> {
+        match key {
+            "stack" => match value.parse::<usize>() {
+                Ok(max) if max > 0 => item.set_max_stack_size(max),
+                _ => return Err((ParseErrorKind::BadNumber, value_span)),
+            },
+            "weight" => match items::parse_kilograms(value) {
+                Some(grams) => item.set_weight(grams),
+                None => return Err((ParseErrorKind::BadNumber, value_span)),
+            },
+            "category" if value.is_empty() => {
+                return Err((
+                    ParseErrorKind::MissingToken {
+                        expected: "category",
+                    },
+                    value_span,
+                ))
+            }
+            "category" => item.set_category(Some(value)),
+            "tags" => {
+                for tag in value.split(',').filter(|tag| !tag.is_empty()) {
+                    item.add_tag(tag);
+                }
+            }
+            "stackable" => match value {
+                "yes" | "true" => item.set_stackable(true),
+                "no" | "false" => item.set_stackable(false),
+                _ => return Err((ParseErrorKind::BadFlag, value_span)),
+            },
+            _ => return Err((ParseErrorKind::UnknownAttribute, value_span)),
+        }
+
+        Ok(())
+    }
+
+    /// Read inventories from an input buffer.
+    ///
+    /// Each inventory starts with a header line and is followed by
+    /// `- <id> <quantity>` or `- "<item name>" <quantity>` lines. The
+    /// header has the form
+    /// `# <capacity> [<max weight in kg>] [accept=<filter>]`, where the
+    /// filter is written as described for `ItemFilter`--e.g.,
+    /// `# 4 accept=10-19,category:ore`.
+    ///
+    /// Exactly one ParsedLine is produced per input line, so the 1-based
+    /// line number of an entry is its index plus one. No input--however
+    /// malformed--causes a panic; problems become `InvalidLine`s.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    pub fn read_inventory_lines<B: BufRead>(ins: B) -> Vec<ParsedLine> {
+        split_lines(ins)
+            .enumerate()
+            .map(|(index, line)| Self::parse_split_line(index + 1, &line))
+            .collect::<Vec<_>>()
+    }
+
+    /// Read inventories from an input buffer and collect the errors for
+    /// all invalid lines.
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///
+    pub fn read_inventory_lines_with_errors<B: BufRead>(ins: B) -> (Vec<ParsedLine>, ParseErrors) {
+        let lines = Self::read_inventory_lines(ins);
+
+        let mut errors = ParseErrors::new();
+        errors.extend(lines.iter().filter_map(ParsedLine::error).cloned());
+
+        (lines, errors)
+    }
+
+    /// Read inventories from an input buffer and check every stack line
+    /// against a catalog.
+    ///
+    /// Stack lines that name an Item missing from the catalog are kept as
+    /// they are, but each one also produces an `UnknownItem` error that
+    /// suggests the closest known Item (if any).
+    ///
+    /// # Arguments
+    ///
+    ///  * `ins` - input source
+    ///  * `catalog` - Items that stack lines may refer to
+    ///
+    pub fn read_inventory_lines_with_catalog<B: BufRead>(
+        ins: B,
+        catalog: &ItemCatalog,
+    ) -> (Vec<ParsedLine>, ParseErrors) {
+        let mut lines = Vec::new();
+        let mut errors = ParseErrors::new();
+
+        for (index, raw_line) in split_lines(ins).enumerate() {
+            let parsed = Self::parse_split_line(index + 1, &raw_line);
+            errors.extend(parsed.error().cloned());
+
+            if let (ParsedLine::ItemStackLine { item, .. }, Ok(line)) = (&parsed, &raw_line) {
+                if catalog.resolve(item).is_none() {
+                    let span = Self::parse_item_ref(line, &tokenize(line))
+                        .map_or(0..line.len(), |(_, span, _)| span);
+                    let suggestion = catalog.suggest(item).map(|known| match item {
+                        ItemRef::Id(_) => format!("{} ({})", known.get_id(), known.get_name()),
+                        ItemRef::Name(_) => ItemRef::Name(known.get_name().to_owned()).to_string(),
+                    });
+
+                    errors.push(ParseError::new(
+                        ParseErrorKind::UnknownItem { suggestion },
+                        index + 1,
+                        span,
+                        line,
+                    ));
                 }
-                _ => {
-                    let line = tokens.join(" ");
-                    ParsedLine::InvalidLine { raw_line: line }
+            }
+
+            lines.push(parsed);
+        }
+
+        (lines, errors)
+    }
+
+    /// Parse one line as produced by `split_lines`, reporting lines that
+    /// are not valid UTF-8.
+    ///
+    fn parse_split_line(line_number: usize, line: &Result<String, String>) -> ParsedLine {
+        match line {
+            Ok(line) => Self::parse_inventory_line(line_number, line),
+            Err(lossy_line) => ParsedLine::InvalidLine {
+                error: ParseError::new(
+                    ParseErrorKind::InvalidEncoding,
+                    line_number,
+                    0..lossy_line.len(),
+                    lossy_line,
+                ),
+            },
+        }
+    }
+
+    pub(crate) fn parse_inventory_line(line_number: usize, line: &str) -> ParsedLine {
+        let tokens = tokenize(line);
+        let invalid = |kind, span: Range<usize>| ParsedLine::InvalidLine {
+            error: ParseError::new(kind, line_number, span, line),
+        };
+        let missing = |expected| {
+            invalid(
+                ParseErrorKind::MissingToken { expected },
+                line.len()..line.len(),
+            )
+        };
+
+        let Some((directive_span, directive)) = tokens.first() else {
+            return ParsedLine::BlankLine;
+        };
+
+        match *directive {
+            "#" => {
+                let Some((size_span, raw_size)) = tokens.get(1) else {
+                    return missing("capacity");
+                };
+                let Ok(max_size) = raw_size.parse() else {
+                    return invalid(ParseErrorKind::BadNumber, size_span.clone());
+                };
+
+                let mut max_weight = None;
+                let mut filter = ItemFilter::Any;
+
+                for (index, (span, token)) in tokens.iter().enumerate().skip(2) {
+                    match token.split_once(ATTRIBUTE_SEPARATOR) {
+                        Some(("accept", value)) => match ItemFilter::parse(value) {
+                            Some(parsed) => filter = parsed,
+                            None => {
+                                let value_span = (span.end - value.len())..span.end;
+                                return invalid(ParseErrorKind::BadFilter, value_span);
+                            }
+                        },
+                        None if index == 2 => match items::parse_kilograms(token) {
+                            Some(grams) => max_weight = Some(grams),
+                            None => return invalid(ParseErrorKind::BadNumber, span.clone()),
+                        },
+                        _ => return invalid(ParseErrorKind::UnknownAttribute, span.clone()),
+                    }
+                }
+
+                ParsedLine::InventoryLine {
+                    max_size,
+                    max_weight,
+                    filter,
                 }
-            })
+            }
+            "-" => {
+                let (item, _, next) = match Self::parse_item_ref(line, &tokens) {
+                    Ok(parsed) => parsed,
+                    Err((kind, span)) => return invalid(kind, span),
+                };
+                let Some((qty_span, raw_qty)) = tokens.get(next) else {
+                    return missing("quantity");
+                };
+                let Ok(quantity) = raw_qty.parse() else {
+                    return invalid(ParseErrorKind::BadNumber, qty_span.clone());
+                };
+
+                ParsedLine::ItemStackLine { item, quantity }
+            }
+            comment if comment.starts_with(COMMENT_MARKER) => {
+                let line = tokens
+                    .iter()
+                    .map(|(_, token)| *token)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                ParsedLine::CommentLine { raw_line: line }
+            }
+            _ => invalid(ParseErrorKind::UnknownDirective, directive_span.clone()),
+        }
+    }
+
+    /// Parse the Item reference that follows the `-` of a stack line--either
+    /// a numeric id or a name in double quotes. Runs of whitespace inside a
+    /// quoted name count as a single space, as in the item file.
+    ///
+    /// # Returns
+    ///
+    /// the reference, its byte range within the line, and the index of the
+    /// first token after it
+    ///
+    fn parse_item_ref(
+        line: &str,
+        tokens: &[(Range<usize>, &str)],
+    ) -> Result<(ItemRef, Range<usize>, usize), LineError> {
+        let end_of_line = line.len()..line.len();
+        let Some((span, raw_ref)) = tokens.get(1) else {
+            return Err((
+                ParseErrorKind::MissingToken {
+                    expected: "item id",
+                },
+                end_of_line,
+            ));
+        };
+
+        if !raw_ref.starts_with('"') {
+            return match raw_ref.parse() {
+                Ok(id) => Ok((ItemRef::Id(id), span.clone(), 2)),
+                Err(_) => Err((ParseErrorKind::BadNumber, span.clone())),
+            };
+        }
+
+        let name_start = span.start + 1;
+        let Some(name_len) = line[name_start..].find('"') else {
+            return Err((
+                ParseErrorKind::MissingToken {
+                    expected: "closing quote",
+                },
+                end_of_line,
+            ));
+        };
+        let name_end = name_start + name_len;
+        let ref_span = span.start..name_end + 1;
+
+        let name = line[name_start..name_end]
+            .split_whitespace()
             .collect::<Vec<_>>()
+            .join(" ");
+        if name.is_empty() {
+            return Err((
+                ParseErrorKind::MissingToken {
+                    expected: "item name",
+                },
+                ref_span,
+            ));
+        }
+
+        let next = tokens
+            .iter()
+            .position(|(token_span, _)| token_span.start > name_end)
+            .unwrap_or(tokens.len());
+
+        Ok((ItemRef::Name(name), ref_span, next))
     }
 }
@@ -0,0 +1,263 @@
+use crate::catalog::{ItemCatalog, ItemRef};
+use crate::error::ParseError;
+use crate::inventory::{AddOutcome, Inventory, RejectReason};
+use crate::items::ItemStack;
+use crate::parser::ParsedLine;
+
+///
+/// Something that happened while processing one line of an inventory file.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogEvent {
+    /// The whole stack was stored, and at least one new slot was opened
+    /// for it. Part of it may also have topped up existing stacks.
+    Stored { stack: ItemStack },
+
+    /// The whole stack was merged into stacks already in the Inventory
+    /// without opening a new slot.
+    Merged { stack: ItemStack },
+
+    /// Only part of the stack was stored.
+    Partial {
+        accepted: usize,
+        leftover: ItemStack,
+        reason: RejectReason,
+    },
+
+    /// None of the stack was stored.
+    Discarded {
+        stack: ItemStack,
+        reason: RejectReason,
+    },
+
+    /// The line referred to an Item missing from the catalog.
+    UnknownItem { item: ItemRef, quantity: usize },
+
+    /// The stack line came before the first header, so there was no
+    /// Inventory to store it in.
+    NoInventory { item: ItemRef, quantity: usize },
+
+    /// The line could not be parsed.
+    InvalidLine { error: ParseError },
+
+    /// The line was a comment.
+    Comment { text: String },
+}
+
+impl LogEvent {
+    ///
+    /// Determine whether this event records an attempt to store items--as
+    /// opposed to a line that carried no items.
+    ///
+    pub fn is_item_event(&self) -> bool {
+        !matches!(
+            self,
+            LogEvent::InvalidLine { .. } | LogEvent::Comment { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogEvent::Stored { stack } => {
+                write!(
+                    f,
+                    "{:9} ({:>2}) {}",
+                    "Stored",
+                    stack.size(),
+                    stack.get_item().get_name()
+                )
+            }
+            LogEvent::Merged { stack } => {
+                write!(
+                    f,
+                    "{:9} ({:>2}) {}",
+                    "Merged",
+                    stack.size(),
+                    stack.get_item().get_name()
+                )
+            }
+            LogEvent::Partial {
+                accepted,
+                leftover,
+                reason,
+            } => write!(
+                f,
+                "{:9} ({:>2}) {} ({} discarded: {})",
+                "Partial",
+                accepted,
+                leftover.get_item().get_name(),
+                leftover.size(),
+                reason
+            ),
+            LogEvent::Discarded { stack, reason } => write!(
+                f,
+                "{:9} ({:>2}) {} ({})",
+                "Discarded",
+                stack.size(),
+                stack.get_item().get_name(),
+                reason
+            ),
+            LogEvent::UnknownItem { item, quantity } => {
+                write!(f, "{:9} ({:>2}) {}", "Unknown", quantity, item)
+            }
+            LogEvent::NoInventory { item, quantity } => write!(
+                f,
+                "{:9} ({:>2}) {} (no inventory)",
+                "Discarded", quantity, item
+            ),
+            LogEvent::InvalidLine { error } => write!(f, "{:9} {}", "Invalid", error),
+            LogEvent::Comment { text } => write!(f, "{:9} {}", "Comment", text),
+        }
+    }
+}
+
+///
+/// A LogEvent along with where it came from.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    /// 1-based line number within the inventory file.
+    pub line: usize,
+
+    /// Index of the Inventory the line belongs to, or `None` for lines
+    /// before the first header.
+    pub inventory: Option<usize>,
+
+    pub event: LogEvent,
+}
+
+///
+/// Everything produced by processing an inventory file.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProcessingReport {
+    /// Inventories in the order their headers appear.
+    pub inventories: Vec<Inventory>,
+
+    /// One entry per processed line (headers excepted), in file order.
+    pub log: Vec<LogEntry>,
+}
+
+impl ProcessingReport {
+    ///
+    /// Iterate over the log entries for one Inventory.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - position of the Inventory in `inventories`
+    ///
+    pub fn log_for(&self, index: usize) -> impl Iterator<Item = &LogEntry> {
+        self.log
+            .iter()
+            .filter(move |entry| entry.inventory == Some(index))
+    }
+
+    ///
+    /// Count the stack lines that referred to an Item missing from the
+    /// catalog.
+    ///
+    pub fn unknown_items(&self) -> usize {
+        self.log
+            .iter()
+            .filter(|entry| matches!(entry.event, LogEvent::UnknownItem { .. }))
+            .count()
+    }
+}
+
+///
+/// Build the Inventories described by a parsed inventory file.
+///
+/// Each `InventoryLine` starts a new Inventory, and each following
+/// `ItemStackLine` is stored in it. Every other line is recorded in the
+/// log. Stack lines that appear before the first header have nowhere to go
+/// and are logged as `NoInventory`.
+///
+/// # Arguments
+///
+///  * `all_inventory_lines` - every line of the inventory file
+///  * `catalog` - Items that stack lines may refer to
+///
+pub fn process_inventory_requests(
+    all_inventory_lines: &[ParsedLine],
+    catalog: &ItemCatalog,
+) -> ProcessingReport {
+    let mut report = ProcessingReport::default();
+
+    for (index, line) in all_inventory_lines.iter().enumerate() {
+        let current = report.inventories.len().checked_sub(1);
+
+        let event = match line {
+            ParsedLine::InventoryLine {
+                max_size,
+                max_weight,
+                filter,
+            } => {
+                let mut inventory = Inventory::new(*max_size);
+                inventory.set_max_weight(*max_weight);
+                inventory.set_filter(filter.clone());
+
+                report.inventories.push(inventory);
+                continue;
+            }
+            ParsedLine::BlankLine => continue,
+            ParsedLine::ItemStackLine { item, quantity } => {
+                match (report.inventories.last_mut(), catalog.resolve(item)) {
+                    (None, _) => LogEvent::NoInventory {
+                        item: item.clone(),
+                        quantity: *quantity,
+                    },
+                    (Some(inventory), Some(known_item)) => {
+                        let stack = ItemStack::new(known_item.clone(), *quantity);
+                        let outcome = inventory.add_items(stack.clone());
+                        outcome_event(stack, outcome)
+                    }
+                    (Some(_), None) => LogEvent::UnknownItem {
+                        item: item.clone(),
+                        quantity: *quantity,
+                    },
+                }
+            }
+            ParsedLine::CommentLine { raw_line } => LogEvent::Comment {
+                text: raw_line.clone(),
+            },
+            ParsedLine::InvalidLine { error } => LogEvent::InvalidLine {
+                error: error.clone(),
+            },
+        };
+
+        report.log.push(LogEntry {
+            line: index + 1,
+            inventory: current,
+            event,
+        });
+    }
+
+    report
+}
+
+///
+/// Turn the result of one attempt to store a stack into a LogEvent.
+///
+/// # Arguments
+///
+///  * `stack` - the stack that was to be stored
+///  * `outcome` - what `Inventory::add_items` did with it
+///
+fn outcome_event(stack: ItemStack, outcome: AddOutcome) -> LogEvent {
+    match outcome {
+        AddOutcome::Merged { .. } => LogEvent::Merged { stack },
+        AddOutcome::NewSlot { .. } => LogEvent::Stored { stack },
+        AddOutcome::Partial {
+            accepted,
+            leftover,
+            reason,
+        } => LogEvent::Partial {
+            accepted,
+            leftover,
+            reason,
+        },
+        AddOutcome::Rejected { reason, .. } => LogEvent::Discarded { stack, reason },
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::items::ItemStack;
+
+///
+/// Indexed storage for the slots of an Inventory.
+///
+/// Each ItemStack lives at a fixed position (its slot index) for as long
+/// as it is stored. New stacks take the lowest free position. Iteration
+/// follows insertion order, which is tracked separately so that reusing a
+/// freed position does not move a new stack ahead of older ones.
+///
+/// An index from Item id to positions keeps lookups by id independent of
+/// the number of occupied slots.
+///
+/// Stacks handed out by `get_mut` may have their quantity changed, but
+/// must keep their Item--otherwise the id index goes stale.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Slots {
+    ///
+    /// Stack stored at each position. Never ends in `None`.
+    ///
+    positions: Vec<Option<ItemStack>>,
+
+    ///
+    /// Occupied positions for each Item id, in ascending order.
+    ///
+    by_id: HashMap<u64, Vec<usize>>,
+
+    ///
+    /// Unoccupied positions below `positions.len()`.
+    ///
+    free: BTreeSet<usize>,
+
+    ///
+    /// Occupied positions, keyed by the sequence number of their stack.
+    ///
+    order: BTreeMap<u64, usize>,
+
+    ///
+    /// Sequence number of the stack at each occupied position.
+    ///
+    sequence_at: HashMap<usize, u64>,
+
+    ///
+    /// Sequence number for the next inserted stack.
+    ///
+    next_sequence: u64,
+}
+
+impl Slots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Determine the number of occupied positions.
+    ///
+    pub fn len(&self) -> usize {
+        self.positions.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Retrieve one past the highest occupied position.
+    ///
+    pub fn end(&self) -> usize {
+        self.positions.len()
+    }
+
+    ///
+    /// Determine the position the next inserted stack will occupy.
+    ///
+    pub fn next_free(&self) -> usize {
+        self.free.first().copied().unwrap_or(self.positions.len())
+    }
+
+    ///
+    /// Retrieve the stack at a position.
+    ///
+    pub fn get(&self, position: usize) -> Option<&ItemStack> {
+        self.positions.get(position).and_then(Option::as_ref)
+    }
+
+    ///
+    /// Retrieve the stack at a position for modification.
+    ///
+    pub fn get_mut(&mut self, position: usize) -> Option<&mut ItemStack> {
+        self.positions.get_mut(position).and_then(Option::as_mut)
+    }
+
+    ///
+    /// Retrieve the positions holding stacks of an Item.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Item id to look up
+    ///
+    /// # Returns
+    ///
+    /// occupied positions in ascending order
+    ///
+    pub fn positions_of(&self, id: u64) -> &[usize] {
+        self.by_id.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    ///
+    /// Store a stack in the lowest free position.
+    ///
+    /// # Returns
+    ///
+    /// the position the stack now occupies
+    ///
+    pub fn insert(&mut self, stack: ItemStack) -> usize {
+        let position = self.next_free();
+        let sequence = self.advance_sequence();
+        self.occupy(position, sequence, stack);
+
+        position
+    }
+
+    ///
+    /// Store a stack at a specific position, which must be free.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - destination position
+    ///
+    /// * `stack` - stack to store
+    ///
+    /// # Returns
+    ///
+    /// `Err` holding the stack if the position was already occupied
+    ///
+    pub fn insert_at(&mut self, position: usize, stack: ItemStack) -> Result<(), ItemStack> {
+        if self.get(position).is_some() {
+            return Err(stack);
+        }
+
+        let sequence = self.advance_sequence();
+        self.occupy(position, sequence, stack);
+
+        Ok(())
+    }
+
+    ///
+    /// Exchange the stacks at two positions. Either (or both) may be free.
+    /// Each stack keeps its place in insertion order.
+    ///
+    pub fn swap(&mut self, first: usize, second: usize) {
+        let first_entry = self.vacate(first);
+        let second_entry = self.vacate(second);
+
+        if let Some((sequence, stack)) = first_entry {
+            self.occupy(second, sequence, stack);
+        }
+        if let Some((sequence, stack)) = second_entry {
+            self.occupy(first, sequence, stack);
+        }
+    }
+
+    ///
+    /// Hand out the sequence number for a newly inserted stack.
+    ///
+    fn advance_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        sequence
+    }
+
+    ///
+    /// Store a stack at a position that is known to be free.
+    ///
+    /// # Arguments
+    ///
+    /// * `sequence` - the stack's place in insertion order
+    ///
+    fn occupy(&mut self, position: usize, sequence: u64, stack: ItemStack) {
+        if position >= self.positions.len() {
+            self.free.extend(self.positions.len()..position);
+            self.positions.resize(position + 1, None);
+        } else {
+            self.free.remove(&position);
+        }
+
+        let ids = self.by_id.entry(stack.get_item().get_id()).or_default();
+        if let Err(offset) = ids.binary_search(&position) {
+            ids.insert(offset, position);
+        }
+
+        self.order.insert(sequence, position);
+        self.sequence_at.insert(position, sequence);
+        self.positions[position] = Some(stack);
+    }
+
+    ///
+    /// Remove the stack at a position, freeing the position.
+    ///
+    pub fn take(&mut self, position: usize) -> Option<ItemStack> {
+        self.vacate(position).map(|(_, stack)| stack)
+    }
+
+    ///
+    /// Remove the stack at a position along with its sequence number.
+    ///
+    fn vacate(&mut self, position: usize) -> Option<(u64, ItemStack)> {
+        let stack = self.positions.get_mut(position)?.take()?;
+
+        let id = stack.get_item().get_id();
+        if let Some(ids) = self.by_id.get_mut(&id) {
+            if let Ok(offset) = ids.binary_search(&position) {
+                ids.remove(offset);
+            }
+            if ids.is_empty() {
+                self.by_id.remove(&id);
+            }
+        }
+
+        self.free.insert(position);
+        while let Some(None) = self.positions.last() {
+            self.positions.pop();
+            self.free.pop_last();
+        }
+
+        let sequence = self.sequence_at.remove(&position)?;
+        self.order.remove(&sequence);
+
+        Some((sequence, stack))
+    }
+
+    ///
+    /// Remove every stack.
+    ///
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.by_id.clear();
+        self.free.clear();
+        self.order.clear();
+        self.sequence_at.clear();
+    }
+
+    ///
+    /// Iterate over occupied positions in the order their stacks were
+    /// inserted.
+    ///
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &ItemStack)> {
+        self.order
+            .values()
+            .filter_map(|&position| self.get(position).map(|stack| (position, stack)))
+    }
+
+    ///
+    /// Remove every stack for which a predicate holds.
+    ///
+    /// # Returns
+    ///
+    /// the removed stacks in insertion order
+    ///
+    pub fn drain_matching<P>(&mut self, mut predicate: P) -> Vec<ItemStack>
+    where
+        P: FnMut(&ItemStack) -> bool,
+    {
+        let doomed: Vec<usize> = self
+            .iter()
+            .filter(|(_, stack)| predicate(stack))
+            .map(|(position, _)| position)
+            .collect();
+
+        doomed
+            .into_iter()
+            .filter_map(|position| self.take(position))
+            .collect()
+    }
+}
+
+impl PartialEq for Slots {
+    fn eq(&self, other: &Self) -> bool {
+        self.positions == other.positions
+    }
+}
+
+impl std::hash::Hash for Slots {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.positions.hash(state);
+    }
+}
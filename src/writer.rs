@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use crate::inventory::Inventory;
+use crate::items::{self, ItemStack};
+
+///
+/// Writes inventories in the inventory file format read by
+/// `Parser::read_inventory_lines`--the inverse of parsing.
+///
+/// Each Inventory becomes a `# <capacity> [<max weight>] [accept=<filter>]`
+/// header followed by one `- <item> <quantity>` line per stack, in the
+/// order the stacks were stored (as yielded by `Inventory::iter`).
+///
+/// The format does not record slot positions, so reading the output back
+/// stores each stack with `Inventory::add_items`. That reproduces any
+/// Inventory that was itself filled by `add_items`. An Inventory with
+/// gaps between slots, or with more than one partial stack of an Item,
+/// comes back compacted instead.
+///
+#[derive(Clone, Debug, Default)]
+pub struct InventoryWriter {
+    /// Write a `// Inventory <n>` line before each header.
+    comments: bool,
+
+    /// Refer to Items by quoted name rather than by id.
+    item_names: bool,
+}
+
+impl InventoryWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Choose whether each Inventory is preceded by a numbered comment.
+    ///
+    pub fn set_comments(&mut self, comments: bool) {
+        self.comments = comments;
+    }
+
+    ///
+    /// Choose whether stack lines refer to Items by quoted name (e.g.,
+    /// `- "HP Potion" 10`) instead of by id. Names that contain a double
+    /// quote or irregular spacing would not read back as the same name, so
+    /// they fall back to the id.
+    ///
+    pub fn set_item_names(&mut self, item_names: bool) {
+        self.item_names = item_names;
+    }
+
+    ///
+    /// Write inventories in the inventory file format.
+    ///
+    /// # Arguments
+    ///
+    ///  * `outs` - output destination
+    ///  * `inventories` - inventories to write, in order
+    ///
+    pub fn write<W: Write>(&self, mut outs: W, inventories: &[Inventory]) -> io::Result<()> {
+        for (index, inventory) in inventories.iter().enumerate() {
+            if self.comments {
+                writeln!(outs, "// Inventory {}", index + 1)?;
+            }
+
+            write!(outs, "# {}", inventory.total_slots())?;
+            if let Some(max_weight) = inventory.get_max_weight() {
+                write!(outs, " {}", items::format_kilograms(max_weight))?;
+            }
+            let filter = inventory.get_filter().to_string();
+            if !filter.is_empty() {
+                write!(outs, " accept={}", filter)?;
+            }
+            writeln!(outs)?;
+
+            for stack in inventory.iter() {
+                writeln!(outs, "- {} {}", self.item_ref(stack), stack.size())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Write inventories to a String in the inventory file format.
+    ///
+    pub fn write_to_string(&self, inventories: &[Inventory]) -> String {
+        let mut buffer = Vec::new();
+        // Writing to a Vec cannot fail, and all parts are valid UTF-8
+        let _ = self.write(&mut buffer, inventories);
+
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    fn item_ref(&self, stack: &ItemStack) -> String {
+        let item = stack.get_item();
+
+        let name = item.get_name();
+        let quotable =
+            !name.contains('"') && name.split_whitespace().collect::<Vec<_>>().join(" ") == name;
+
+        if self.item_names && quotable {
+            format!("\"{}\"", name)
+        } else {
+            item.get_id().to_string()
+        }
+    }
+}
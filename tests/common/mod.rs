@@ -0,0 +1,28 @@
+//! Fixtures shared by the integration tests.
+//!
+//! Each test file uses only some of these.
+#![allow(dead_code)]
+use rstest::fixture;
+
+use rust_inventory::prelude::*;
+
+#[fixture]
+pub fn test_items() -> [Item; 3] {
+    [
+        Item::new(0, String::from("Diamond Boots")),
+        Item::new(1, String::from("Tomato")),
+        Item::new(2, String::from("Unbreaking Gold Shovel")),
+    ]
+}
+
+#[fixture]
+pub fn iron_ore() -> Item {
+    let mut iron_ore = Item::new(5, String::from("Iron Ore"));
+    iron_ore.set_weight(2_500);
+    iron_ore.set_max_stack_size(64);
+    iron_ore.set_category(Some("ore"));
+    iron_ore.add_tag("smeltable");
+    iron_ore.add_tag("heavy");
+
+    iron_ore
+}
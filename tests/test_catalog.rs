@@ -0,0 +1,131 @@
+use std::io::BufReader;
+use stringreader::StringReader;
+
+use hamcrest2::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::catalog::ItemRef;
+use rust_inventory::error::{CatalogError, ParseErrorKind};
+use rust_inventory::prelude::*;
+
+mod common;
+use common::test_items;
+
+#[rstest]
+fn test_lookup(test_items: [Item; 3]) {
+    let catalog = ItemCatalog::from_items(test_items.clone()).unwrap();
+
+    assert_that!(catalog.len(), equal_to(3));
+    assert_that!(catalog.get(1), equal_to(Some(&test_items[1])));
+    assert_that!(catalog.get(7), equal_to(None));
+    assert_that!(
+        catalog.find_by_name("unbreaking GOLD shovel"),
+        equal_to(Some(&test_items[2]))
+    );
+    assert_that!(catalog.find_by_name("Gold Shovel"), equal_to(None));
+
+    let ids: Vec<_> = catalog.iter().map(Item::get_id).collect();
+    assert_that!(ids, equal_to(vec![0, 1, 2]));
+}
+
+#[rstest]
+fn test_conflicts_are_rejected(test_items: [Item; 3]) {
+    let mut catalog = ItemCatalog::from_items(test_items).unwrap();
+
+    assert_that!(
+        catalog.insert(Item::new(1, String::from("Potato"))),
+        equal_to(Err(CatalogError::DuplicateId {
+            id: 1,
+            existing: String::from("Tomato")
+        }))
+    );
+    assert_that!(
+        catalog.insert(Item::new(5, String::from("TOMATO"))),
+        equal_to(Err(CatalogError::DuplicateName {
+            name: String::from("TOMATO"),
+            existing: 1
+        }))
+    );
+    assert_that!(catalog.len(), equal_to(3));
+    assert_that!(catalog.get(5), equal_to(None));
+}
+
+#[rstest]
+fn test_read_catalog_reports_conflicts() {
+    let sreader = StringReader::new("1 Tomato\n2 Iron Ore\n3 iron  ore\n1 Potato\n4 Coal");
+
+    let (catalog, errors) = Parser::read_catalog_with_errors(BufReader::new(sreader));
+
+    let ids: Vec<_> = catalog.iter().map(Item::get_id).collect();
+    assert_that!(ids, equal_to(vec![1, 2, 4]));
+
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|error| (error.line, error.kind.clone(), error.offending_text()))
+        .collect();
+    assert_that!(
+        summary,
+        equal_to(vec![
+            (
+                3,
+                ParseErrorKind::DuplicateName { first_line: 2 },
+                "iron  ore"
+            ),
+            (4, ParseErrorKind::DuplicateId { first_line: 1 }, "1"),
+        ])
+    );
+}
+
+#[rstest]
+fn test_suggestions(test_items: [Item; 3]) {
+    let mut catalog = ItemCatalog::from_items(test_items.clone()).unwrap();
+    catalog
+        .insert(Item::new(57, String::from("Diamond Block")))
+        .unwrap();
+
+    let suggested_id = |item_ref: ItemRef| catalog.suggest(&item_ref).map(Item::get_id);
+
+    assert_that!(
+        suggested_id(ItemRef::Name(String::from("diamond blok"))),
+        equal_to(Some(57))
+    );
+    assert_that!(
+        suggested_id(ItemRef::Name(String::from("Tomatoe"))),
+        equal_to(Some(1))
+    );
+    assert_that!(
+        suggested_id(ItemRef::Name(String::from("Potato Salad"))),
+        equal_to(None)
+    );
+    assert_that!(suggested_id(ItemRef::Id(75)), equal_to(Some(57)));
+    assert_that!(suggested_id(ItemRef::Id(58)), equal_to(Some(57)));
+    assert_that!(suggested_id(ItemRef::Id(5)), equal_to(None));
+    assert_that!(
+        catalog.resolve(&ItemRef::Name(String::from("TOMATO"))),
+        equal_to(Some(&test_items[1]))
+    );
+}
+
+#[rstest]
+fn test_read_inventory_lines_with_catalog(test_items: [Item; 3]) {
+    let catalog = ItemCatalog::from_items(test_items).unwrap();
+    let sreader = StringReader::new("# 4\n- \"tomato\" 2\n- \"Diamond Boot\" 1\n- 12 3\n- 7 1");
+
+    let (lines, errors) =
+        Parser::read_inventory_lines_with_catalog(BufReader::new(sreader), &catalog);
+
+    assert_that!(lines.len(), equal_to(5));
+    assert_that!(lines.iter().all(|line| line.error().is_none()), is(true));
+
+    let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_that!(
+        messages,
+        equal_to(vec![
+            String::from(
+                "<input>:3:3: unknown item (did you mean \"Diamond Boots\"?) `\"Diamond Boot\"`"
+            ),
+            String::from("<input>:4:3: unknown item `12`"),
+            String::from("<input>:5:3: unknown item `7`"),
+        ])
+    );
+}
@@ -0,0 +1,101 @@
+use std::process::{Command, Output};
+
+use hamcrest2::prelude::*;
+use rstest::rstest;
+
+#[cfg(feature = "serde")]
+use rust_inventory::prelude::*;
+
+fn run_inventory(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust_inventory"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("binary should run")
+}
+
+#[rstest]
+#[case::lenient(&["itemList-01.txt", "inventoryList-02.txt"], true)]
+#[case::strict_with_unknown_items(&["--strict", "itemList-01.txt", "inventoryList-02.txt"], false)]
+#[case::strict_without_unknown_items(&["--strict", "itemList-00.txt", "inventoryList-00.txt"], true)]
+fn test_strict_exit_status(#[case] args: &[&str], #[case] succeeds: bool) {
+    let output = run_inventory(args);
+
+    assert_that!(output.status.success(), is(succeeds));
+}
+
+#[rstest]
+fn test_unknown_items_are_logged() {
+    let output = run_inventory(&["itemList-01.txt", "inventoryList-02.txt"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_that!(stdout.contains("Unknown   ( 4) 57\n"), is(true));
+    assert_that!(stdout.contains("Unknown items: 13\n"), is(true));
+    assert_that!(
+        stderr.contains("inventoryList-02.txt:4:3: unknown item `57`"),
+        is(true)
+    );
+}
+
+#[rstest]
+fn test_json_output() {
+    let output = run_inventory(&[
+        "--format",
+        "json",
+        "itemList-00.txt",
+        "inventoryList-00.txt",
+    ]);
+
+    assert_that!(output.status.success(), is(cfg!(feature = "serde")));
+    #[cfg(feature = "serde")]
+    {
+        let inventories =
+            Parser::read_inventories_json(output.stdout.as_slice()).expect("output should parse");
+        assert_that!(inventories.len(), equal_to(3));
+    }
+}
+
+#[rstest]
+fn test_fmt_check_and_rewrite() {
+    let path = std::env::temp_dir().join(format!("fmt-check-{}.txt", std::process::id()));
+    std::fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/inventoryList-01.txt"),
+        &path,
+    )
+    .unwrap();
+    let path_arg = path.to_str().unwrap();
+
+    let unformatted = run_inventory(&["fmt", "--check", path_arg]);
+    assert_that!(unformatted.status.success(), is(false));
+
+    let rewrite = run_inventory(&["fmt", path_arg]);
+    assert_that!(rewrite.status.success(), is(true));
+
+    let formatted = run_inventory(&["fmt", "--check", path_arg]);
+    assert_that!(formatted.status.success(), is(true));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[rstest]
+fn test_csv_items_and_output() {
+    let path = std::env::temp_dir().join(format!("items-{}.csv", std::process::id()));
+    std::fs::write(&path, "id,name\n1,HP Potion\n2,\"Mana, Blue\"\n").unwrap();
+
+    let output = run_inventory(&[
+        "--format=csv",
+        path.to_str().unwrap(),
+        "inventoryList-00.txt",
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_that!(output.status.success(), is(true));
+    assert_that!(
+        stdout.starts_with("inventory,slot,id,name,quantity\n0,0,"),
+        is(true)
+    );
+    assert_that!(stdout.contains(",2,\"Mana, Blue\","), is(true));
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,108 @@
+use hamcrest2::prelude::*;
+use proptest::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::cst::{InventoryDocument, TokenKind};
+use rust_inventory::error::EditError;
+use rust_inventory::prelude::*;
+
+const SAMPLE: &str = "// Chest\n# 3   2.5\n- 2  5  // spare\n- \"Iron  Ore\" 12\n";
+
+#[rstest]
+fn test_tokens_and_comments() {
+    let document = InventoryDocument::parse(SAMPLE);
+    let line = &document.lines()[2];
+
+    let kinds: Vec<_> = line.tokens().iter().map(|token| token.kind).collect();
+    assert_that!(
+        kinds,
+        equal_to(vec![
+            TokenKind::Directive,
+            TokenKind::Whitespace,
+            TokenKind::Number,
+            TokenKind::Whitespace,
+            TokenKind::Number,
+            TokenKind::Whitespace,
+            TokenKind::Comment,
+        ])
+    );
+    assert_that!(line.comment(), equal_to(Some("// spare")));
+    assert_that!(document.lines()[0].comment(), equal_to(Some("// Chest")));
+    assert_that!(
+        document.lines()[3].token_text(&document.lines()[3].tokens()[2]),
+        equal_to("\"Iron  Ore\"")
+    );
+}
+
+#[rstest]
+fn test_set_quantity_keeps_layout() {
+    let mut document = InventoryDocument::parse(SAMPLE);
+
+    document.set_quantity(2, 12).unwrap();
+    document.set_quantity(3, 7).unwrap();
+
+    assert_that!(
+        document.to_string(),
+        equal_to(String::from(
+            "// Chest\n# 3   2.5\n- 2  12  // spare\n- \"Iron  Ore\" 7\n"
+        ))
+    );
+    assert_that!(
+        document.set_quantity(1, 4),
+        equal_to(Err(EditError::NotAStackLine { index: 1 }))
+    );
+    assert_that!(
+        document.set_quantity(9, 4),
+        equal_to(Err(EditError::LineOutOfRange {
+            index: 9,
+            total_lines: 4
+        }))
+    );
+}
+
+#[rstest]
+#[case::trailing_newline("# 2\r\n- 1 3\r\n", "# 2\r\n- 1 3\r\n- 2 4\r\n")]
+#[case::no_trailing_newline("# 2\n- 1 3", "# 2\n- 1 3\n- 2 4")]
+fn test_insert_and_remove_last_line(#[case] original: &str, #[case] extended: &str) {
+    let mut document = InventoryDocument::parse(original);
+
+    document.insert_line(2, "- 2 4").unwrap();
+    assert_that!(document.to_string(), equal_to(String::from(extended)));
+
+    let removed = document.remove_line(2).unwrap();
+    assert_that!(removed.text(), equal_to("- 2 4"));
+    assert_that!(document.to_string(), equal_to(String::from(original)));
+
+    assert_that!(
+        document.insert_line(0, "# 1\n- 1 1"),
+        equal_to(Err(EditError::LineBreak))
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_parse_then_display_is_lossless(text in "[-#/ \t\r\n0-9a-z=\".]{0,80}") {
+        let document = InventoryDocument::parse(&text);
+
+        prop_assert_eq!(document.to_string(), text);
+
+        for line in document.lines() {
+            let mut end = 0;
+            for token in line.tokens() {
+                prop_assert_eq!(token.span.start, end);
+                end = token.span.end;
+            }
+            prop_assert_eq!(end, line.text().len());
+        }
+    }
+
+    #[test]
+    fn prop_parsed_lines_match_parser(text in "[-#/ \t\r\n0-9a-z=\".]{0,80}") {
+        let document = InventoryDocument::parse(&text);
+
+        prop_assert_eq!(
+            document.parsed_lines(),
+            Parser::read_inventory_lines(text.as_bytes())
+        );
+    }
+}
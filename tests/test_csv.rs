@@ -0,0 +1,131 @@
+use hamcrest2::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::csv;
+use rust_inventory::error::ParseErrorKind;
+use rust_inventory::prelude::*;
+
+mod common;
+use common::iron_ore;
+
+#[rstest]
+fn test_read_catalog(iron_ore: Item) {
+    let text = "id,name,stack,weight,category,tags\n\
+                1,HP Potion,16,,,\n\
+                \n\
+                5,Iron Ore,64,2.5,Ore,\"smeltable,heavy\"\n";
+
+    let (catalog, errors) = Parser::read_catalog_csv(text.as_bytes());
+
+    assert_that!(errors.is_empty(), is(true));
+    assert_that!(catalog.len(), equal_to(2));
+    assert_that!(catalog.get(1).unwrap().get_max_stack_size(), equal_to(16));
+    assert_that!(catalog.get(1).unwrap().get_weight(), equal_to(0));
+    assert_that!(catalog.get(5).unwrap().clone(), equal_to(iron_ore));
+}
+
+#[rstest]
+fn test_columns_in_any_order_and_quoting() {
+    let text = "Name , ID\r\n\"Potion, \"\"Large\"\"\", 7\r\n  Dirt  ,8\r\n";
+
+    let (catalog, errors) = Parser::read_catalog_csv(text.as_bytes());
+
+    assert_that!(errors.is_empty(), is(true));
+    assert_that!(
+        catalog.get(7).unwrap().get_name(),
+        equal_to("Potion, \"Large\"")
+    );
+    assert_that!(catalog.get(8).unwrap().get_name(), equal_to("Dirt"));
+}
+
+#[rstest]
+fn test_byte_order_mark_and_unnamed_columns() {
+    let text = "\u{feff}id,,name,\n1,ignored,HP Potion,\n2,,Dirt,notes\n";
+
+    let (catalog, errors) = Parser::read_catalog_csv(text.as_bytes());
+
+    assert_that!(errors.is_empty(), is(true));
+    assert_that!(catalog.get(1).unwrap().get_name(), equal_to("HP Potion"));
+    assert_that!(catalog.get(2).unwrap().get_name(), equal_to("Dirt"));
+}
+
+#[rstest]
+#[case::bad_id("x1,Dirt,", ParseErrorKind::BadNumber, 0..2)]
+#[case::missing_name("3,,", ParseErrorKind::MissingToken { expected: "item name" }, 3..3)]
+#[case::bad_stack("3,Dirt,0", ParseErrorKind::BadNumber, 7..8)]
+#[case::extra_field("3,Dirt,4,5", ParseErrorKind::UnknownAttribute, 9..10)]
+#[case::unclosed_quote("3,\"Dirt,4", ParseErrorKind::MissingToken { expected: "closing quote" }, 2..9)]
+#[case::duplicate_id("1,Dirt,", ParseErrorKind::DuplicateId { first_line: 2 }, 0..1)]
+#[case::duplicate_name("3,hp potion,", ParseErrorKind::DuplicateName { first_line: 2 }, 2..11)]
+fn test_rejected_rows(
+    #[case] row: &str,
+    #[case] kind: ParseErrorKind,
+    #[case] span: std::ops::Range<usize>,
+) {
+    let text = format!("id,name,stack\n1,HP Potion,16\n{}\n", row);
+
+    let (catalog, errors) = Parser::read_catalog_csv(text.as_bytes());
+    let error = errors.iter().next().unwrap();
+
+    assert_that!(catalog.len(), equal_to(1));
+    assert_that!(errors.len(), equal_to(1));
+    assert_that!(error.line, equal_to(3));
+    assert_that!(error.kind.clone(), equal_to(kind));
+    assert_that!(error.span.clone(), equal_to(span));
+}
+
+#[rstest]
+#[case::unknown_column("id,name,colour", ParseErrorKind::UnknownAttribute)]
+#[case::duplicate_column("id,name,ID", ParseErrorKind::DuplicateColumn)]
+#[case::missing_id("name,stack", ParseErrorKind::MissingToken { expected: "id column" })]
+fn test_bad_header_reads_nothing(#[case] header: &str, #[case] kind: ParseErrorKind) {
+    let text = format!("{}\n1,HP Potion,16\n", header);
+
+    let (catalog, errors) = Parser::read_catalog_csv(text.as_bytes());
+
+    assert_that!(catalog.is_empty(), is(true));
+    assert_that!(errors.len(), equal_to(1));
+    assert_that!(
+        errors.iter().next().map(|error| error.kind.clone()),
+        equal_to(Some(kind))
+    );
+}
+
+#[rstest]
+fn test_catalog_round_trip(iron_ore: Item) {
+    let mut trowel = Item::new(9, String::from("Trowel, \"Rusty\""));
+    trowel.set_stackable(false);
+    let catalog =
+        ItemCatalog::from_items([Item::new(1, String::from("HP Potion")), iron_ore, trowel])
+            .unwrap();
+
+    let mut buffer = Vec::new();
+    csv::write_catalog(&mut buffer, &catalog).unwrap();
+    let (read_back, errors) = Parser::read_catalog_csv(buffer.as_slice());
+
+    assert_that!(errors.is_empty(), is(true));
+    assert_that!(read_back.into_items(), equal_to(catalog.into_items()));
+}
+
+#[rstest]
+fn test_write_inventory_stacks(iron_ore: Item) {
+    let mut first = Inventory::new(3);
+    first.add_items(ItemStack::new(Item::new(1, String::from("HP Potion")), 10));
+    first.add_items(ItemStack::new(iron_ore.clone(), 5));
+    let empty = Inventory::new(2);
+    let mut last = Inventory::new(2);
+    last.add_items(ItemStack::new(Item::new(2, String::from("Mana, Blue")), 1));
+
+    let mut buffer = Vec::new();
+    csv::write_inventory_stacks(&mut buffer, &[first, empty, last]).unwrap();
+
+    assert_that!(
+        String::from_utf8(buffer).unwrap(),
+        equal_to(
+            "inventory,slot,id,name,quantity\n\
+             0,0,1,HP Potion,10\n\
+             0,1,5,Iron Ore,5\n\
+             2,0,2,\"Mana, Blue\",1\n"
+        )
+    );
+}
@@ -0,0 +1,108 @@
+use hamcrest2::prelude::*;
+use proptest::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::error::ParseErrorKind;
+use rust_inventory::formatter::{self, FileKind};
+use rust_inventory::parser::ParsedLine;
+use rust_inventory::prelude::*;
+
+#[rstest]
+#[case::inventories("// Chest\n# 3\n- 1 2", Some(FileKind::Inventories))]
+#[case::items("\n//Ores\n5 Iron Ore", Some(FileKind::Items))]
+#[case::only_comments("// nothing here\n", None)]
+#[case::neither("Iron Ore 5", None)]
+fn test_detect(#[case] text: &str, #[case] expected: Option<FileKind>) {
+    assert_that!(FileKind::detect(text), equal_to(expected));
+}
+
+#[rstest]
+fn test_format_inventories() {
+    let text = "///First\n#   3   2.50\n\n- 7 5\n- 4  13 //spare\n- \"Iron   Ore\" 2\n  \n# 1 accept=1-9\n- 100 1\r\n";
+
+    assert_that!(
+        formatter::format_inventories(text).unwrap(),
+        equal_to(String::from(
+            "// First\n# 3 2.5\n- 7           5\n- 4          13  // spare\n- \"Iron Ore\"  2\n\
+             # 1 accept=1-9\n- 100 1\n"
+        ))
+    );
+    assert_that!(
+        formatter::format_inventories("# 2\n- \"Äpfel\" 3\n- \"Dirt\" 12\n").unwrap(),
+        equal_to(String::from("# 2\n- \"Äpfel\"  3\n- \"Dirt\"  12\n"))
+    );
+}
+
+#[rstest]
+fn test_format_inventories_refuses_to_lose_text() {
+    let errors = formatter::format_inventories("# 2\n- 1 5 extra\n- 2 x").unwrap_err();
+
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|error| (error.line, error.kind.clone(), error.offending_text()))
+        .collect();
+    assert_that!(
+        summary,
+        equal_to(vec![
+            (2, ParseErrorKind::UnknownAttribute, "extra"),
+            (3, ParseErrorKind::BadNumber, "x"),
+        ])
+    );
+}
+
+#[rstest]
+fn test_format_items() {
+    let text = "//Potions\n12 HP Potion stack=16\n\n 3 Dirt\n// Ores\n5 Iron Ore weight=2.50 category=Ore\n//end\n";
+
+    assert_that!(
+        formatter::format_items(text).unwrap(),
+        equal_to(String::from(
+            " 3 Dirt\n// Ores\n 5 Iron Ore  weight=2.5 category=ore\n\
+             // Potions\n12 HP Potion stack=16\n// end\n"
+        ))
+    );
+    assert_that!(
+        formatter::format_items("1 Tomato\n1 Potato").is_err(),
+        is(true)
+    );
+    assert_that!(
+        formatter::format_items("2 Äpfel stack=4\n1 Dirt stack=64\n").unwrap(),
+        equal_to(String::from("1 Dirt  stack=64\n2 Äpfel stack=4\n"))
+    );
+}
+
+///
+/// Keep only the lines that affect the inventories built from a file.
+///
+fn meaningful(lines: Vec<ParsedLine>) -> Vec<ParsedLine> {
+    lines
+        .into_iter()
+        .filter(|line| !matches!(line, ParsedLine::CommentLine { .. } | ParsedLine::BlankLine))
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn prop_format_inventories_is_stable(
+        lines in proptest::collection::vec(
+            prop_oneof![
+                "# [1-9]( [0-9]{1,2}(\\.[0-9])?)?",
+                "-  ?[0-9]{1,3} {1,3}[0-9]{1,3}( //[a-z ]{0,6})?",
+                "- \"[A-Za-z ]{1,8}\" [0-9]{1,2}",
+                "/{2,3}[a-z ]{0,8}",
+                " {0,2}",
+            ],
+            0..12,
+        )
+    ) {
+        let text = lines.join("\n");
+
+        if let Ok(formatted) = formatter::format_inventories(&text) {
+            prop_assert_eq!(
+                meaningful(Parser::read_inventory_lines(formatted.as_bytes())),
+                meaningful(Parser::read_inventory_lines(text.as_bytes()))
+            );
+            prop_assert_eq!(formatter::format_inventories(&formatted).unwrap(), formatted);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Golden-file regression tests for the whole program.
+//!
+//! Each entry of `CASES` names an item file, an inventory file, and the
+//! reference output the binary should print to stdout for them. All three
+//! live in the repository root. To add a case, add its reference output
+//! and a row to the table.
+//!
+//! Set `UPDATE_GOLDEN=1` to overwrite the reference outputs with the
+//! current output instead of comparing against them. Only do so for
+//! deliberate changes to the output.
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use similar::TextDiff;
+
+const ROOT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+///
+/// (item file, inventory file, reference output) for every run.
+///
+const CASES: &[(&str, &str, &str)] = &[
+    (
+        "itemList-00.txt",
+        "inventoryList-00.txt",
+        "ref-output-00.txt",
+    ),
+    (
+        "itemList-00.txt",
+        "inventoryList-01.txt",
+        "ref-output-02.txt",
+    ),
+    (
+        "itemList-00.txt",
+        "inventoryList-02.txt",
+        "ref-output-04.txt",
+    ),
+];
+
+///
+/// Run the binary on one pair of input files.
+///
+/// # Returns
+///
+/// everything written to stdout
+///
+fn run_binary(items: &str, inventories: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust_inventory"))
+        .arg(Path::new(ROOT_DIR).join(items))
+        .arg(Path::new(ROOT_DIR).join(inventories))
+        .output()
+        .expect("binary should run");
+
+    assert!(
+        output.status.success(),
+        "{} {} failed: {}",
+        items,
+        inventories,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).expect("output should be UTF-8")
+}
+
+#[test]
+fn test_golden_outputs() {
+    let regenerate = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for (items, inventories, reference) in CASES.iter().copied() {
+        let actual = run_binary(items, inventories);
+        let expected_path = Path::new(ROOT_DIR).join(reference);
+
+        if regenerate {
+            fs::write(&expected_path, &actual).expect("reference output should be writable");
+            continue;
+        }
+
+        let expected = fs::read_to_string(&expected_path).expect("reference output should exist");
+        if expected != actual {
+            let diff = TextDiff::from_lines(&expected, &actual)
+                .unified_diff()
+                .header(reference, "actual")
+                .to_string();
+            failures.push(diff);
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} golden run(s) differ (rerun with UPDATE_GOLDEN=1 to accept):\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
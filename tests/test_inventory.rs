@@ -1,16 +1,13 @@
 use hamcrest2::prelude::*;
 use rstest::{fixture, rstest};
 
+use rust_inventory::error::InventoryError;
+use rust_inventory::filter::ItemFilter;
+use rust_inventory::inventory::{AddOutcome, RejectReason};
 use rust_inventory::prelude::*;
 
-#[fixture]
-fn test_items() -> [Item; 3] {
-    [
-        Item::new(0, String::from("Diamond Boots")),
-        Item::new(1, String::from("Tomato")),
-        Item::new(2, String::from("Unbreaking Gold Shovel")),
-    ]
-}
+mod common;
+use common::test_items;
 
 #[fixture]
 fn empty_inventory() -> Inventory {
@@ -84,14 +81,23 @@ fn test_add_item_with_duplicate_items(test_items: [Item; 3]) {
     assert_that!(a_bag.empty_slots(), equal_to(2));
     assert_that!(a_bag.total_slots(), equal_to(4));
 }
+
+#[fixture]
+fn non_stackable_boots() -> Item {
+    let mut boots = Item::new(0, String::from("Diamond Boots"));
+    boots.set_stackable(false);
+
+    boots
+}
+
 ///
 /// Add ItemStacks to an Inventory and fill it.
-/// Then try to add one more ItemStack that is stackable.
+/// Then try to add one more ItemStack that is **not** stackable.
 ///
 #[rstest]
-fn test_add_item_after_full_with_non_stackable(test_items: [Item; 3]) {
+fn test_add_item_after_full_with_non_stackable(test_items: [Item; 3], non_stackable_boots: Item) {
     let stacks_to_add = [
-        ItemStack::new(test_items[0].clone(), 1),
+        ItemStack::new(non_stackable_boots.clone(), 1),
         ItemStack::new(test_items[1].clone(), 1),
         ItemStack::new(test_items[2].clone(), 1),
     ];
@@ -101,7 +107,16 @@ fn test_add_item_after_full_with_non_stackable(test_items: [Item; 3]) {
     a_bag.add_items(stacks_to_add[0].clone());
     a_bag.add_items(stacks_to_add[1].clone());
 
-    assert_that!(a_bag.add_items(stacks_to_add[2].clone()), is(false));
+    assert_that!(
+        a_bag.add_items(stacks_to_add[2].clone()).is_complete(),
+        is(false)
+    );
+
+    // Another pair of boots needs its own slot--and there is none left
+    assert_that!(
+        a_bag.add_items(stacks_to_add[0].clone()).is_complete(),
+        is(false)
+    );
 
     assert_that!(a_bag.is_full(), is(true));
     assert_that!(a_bag.utilized_slots(), equal_to(2));
@@ -109,9 +124,35 @@ fn test_add_item_after_full_with_non_stackable(test_items: [Item; 3]) {
     assert_that!(a_bag.total_slots(), equal_to(2));
 }
 
+///
+/// Add non-stackable ItemStacks to an Inventory that is not full. Each item
+/// must land in its own slot.
+///
+#[rstest]
+fn test_add_item_not_full_with_non_stackable(non_stackable_boots: Item) {
+    let mut a_bag = Inventory::new(4);
+
+    a_bag.add_items(ItemStack::new(non_stackable_boots.clone(), 1));
+    let outcome = a_bag.add_items(ItemStack::new(non_stackable_boots.clone(), 2));
+
+    assert_that!(outcome.accepted(), equal_to(2));
+    assert_that!(a_bag.utilized_slots(), equal_to(3));
+    assert_that!(a_bag.empty_slots(), equal_to(1));
+    assert_that!(
+        a_bag.to_string().matches("( 1) Diamond Boots").count(),
+        equal_to(3)
+    );
+
+    // Only one more slot is left for the next two pairs
+    let outcome = a_bag.add_items(ItemStack::new(non_stackable_boots, 2));
+    assert_that!(outcome.accepted(), equal_to(1));
+    assert_that!(outcome.leftover().map(ItemStack::size), equal_to(Some(1)));
+    assert_that!(a_bag.is_full(), is(true));
+}
+
 ///
 /// Add ItemStacks to an Inventory and fill it.
-/// Then try to add one more ItemStack that is **not** stackable.
+/// Then try to add one more ItemStack that is stackable.
 ///
 #[rstest]
 fn test_add_item_after_full_with_stackable(test_items: [Item; 3]) {
@@ -126,7 +167,10 @@ fn test_add_item_after_full_with_stackable(test_items: [Item; 3]) {
     a_bag.add_items(stacks_to_add[1].clone());
     a_bag.add_items(stacks_to_add[0].clone());
 
-    assert_that!(a_bag.add_items(stacks_to_add[1].clone()), is(true));
+    assert_that!(
+        a_bag.add_items(stacks_to_add[1].clone()).is_complete(),
+        is(true)
+    );
 
     assert_that!(a_bag.is_full(), is(true));
     assert_that!(a_bag.is_empty(), is(false));
@@ -159,8 +203,7 @@ fn test_to_display(test_items: [Item; 3]) {
 
     let locations = items_as_strings
         .iter()
-        .map(|item_as_str| a_bag_as_str.find(&*item_as_str))
-        .flatten()
+        .filter_map(|item_as_str| a_bag_as_str.find(item_as_str))
         .collect::<Vec<_>>();
 
     let mut sorted_locations = locations.clone();
@@ -168,3 +211,473 @@ fn test_to_display(test_items: [Item; 3]) {
 
     assert_that!(locations, is(equal_to(sorted_locations)));
 }
+
+///
+/// Add more items than fit in one stack and make sure the remainder
+/// spills into new slots--but only while slots are available.
+///
+#[rstest]
+fn test_add_items_overflow_into_new_slots() {
+    let mut ender_pearl = Item::new(368, String::from("Ender Pearl"));
+    ender_pearl.set_max_stack_size(16);
+
+    let mut a_bag = Inventory::new(3);
+
+    let outcome = a_bag.add_items(ItemStack::new(ender_pearl.clone(), 10));
+    assert_that!(outcome.accepted(), equal_to(10));
+    assert_that!(outcome.is_complete(), is(true));
+    assert_that!(a_bag.utilized_slots(), equal_to(1));
+
+    // 6 top off the first stack, 16 fill a new slot, 8 start a third
+    let outcome = a_bag.add_items(ItemStack::new(ender_pearl.clone(), 30));
+    assert_that!(outcome.accepted(), equal_to(30));
+    assert_that!(a_bag.utilized_slots(), equal_to(3));
+
+    // Only 8 more fit in the last stack
+    let outcome = a_bag.add_items(ItemStack::new(ender_pearl.clone(), 20));
+    assert_that!(outcome.accepted(), equal_to(8));
+    assert_that!(outcome.leftover().map(ItemStack::size), equal_to(Some(12)));
+    assert_that!(a_bag.is_full(), is(true));
+
+    let a_bag_as_str = a_bag.to_string();
+    assert_that!(
+        a_bag_as_str.matches("(16) Ender Pearl").count(),
+        equal_to(3)
+    );
+}
+
+///
+/// Stacks of the same Item with different quantities must merge.
+///
+#[rstest]
+fn test_add_items_merges_different_quantities(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(4);
+
+    a_bag.add_items(ItemStack::new(test_items[1].clone(), 3));
+    a_bag.add_items(ItemStack::new(test_items[1].clone(), 5));
+
+    assert_that!(a_bag.utilized_slots(), equal_to(1));
+    assert_that!(
+        a_bag
+            .find_matching_item_stack(&ItemStack::new(test_items[1].clone(), 1))
+            .map(|stack| stack.size()),
+        equal_to(Some(8))
+    );
+    assert_that!(
+        a_bag.find_matching_item_stack(&ItemStack::new(test_items[0].clone(), 8)),
+        is(none())
+    );
+}
+
+#[rstest]
+fn test_add_outcomes(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(1);
+
+    assert_that!(
+        a_bag.add_items(ItemStack::new(test_items[1].clone(), 3)),
+        equal_to(AddOutcome::NewSlot {
+            accepted: 3,
+            new_slots: 1
+        })
+    );
+    assert_that!(
+        a_bag.add_items(ItemStack::new(test_items[1].clone(), 5)),
+        equal_to(AddOutcome::Merged { accepted: 5 })
+    );
+
+    let outcome = a_bag.add_items(ItemStack::new(test_items[0].clone(), 2));
+    let expected = AddOutcome::Rejected {
+        reason: RejectReason::InventoryFull,
+        stack: ItemStack::new(test_items[0].clone(), 2),
+    };
+    assert_that!(&outcome, equal_to(&expected));
+    assert_that!(outcome.accepted(), equal_to(0));
+    assert_that!(
+        outcome.reason(),
+        equal_to(Some(RejectReason::InventoryFull))
+    );
+}
+
+#[rstest]
+fn test_remove_items(test_items: [Item; 3]) {
+    let mut small_tomato = test_items[1].clone();
+    small_tomato.set_max_stack_size(10);
+
+    let mut a_bag = Inventory::new(4);
+    a_bag.add_items(ItemStack::new(test_items[0].clone(), 1));
+    a_bag.add_items(ItemStack::new(small_tomato.clone(), 25));
+    assert_that!(a_bag.utilized_slots(), equal_to(4));
+
+    // Take from the last (partial) stack first, freeing its slot
+    let removed = a_bag.remove_items(1, 7).unwrap();
+    assert_that!(removed, equal_to(ItemStack::new(small_tomato.clone(), 7)));
+    assert_that!(a_bag.quantity_of(1), equal_to(18));
+    assert_that!(a_bag.utilized_slots(), equal_to(3));
+
+    // Asking for too much changes nothing
+    assert_that!(
+        a_bag.remove_items(1, 19),
+        equal_to(Err(InventoryError::Shortage {
+            id: 1,
+            requested: 19,
+            available: 18
+        }))
+    );
+    assert_that!(a_bag.quantity_of(1), equal_to(18));
+
+    assert_that!(
+        a_bag.remove_items(2, 1),
+        equal_to(Err(InventoryError::Shortage {
+            id: 2,
+            requested: 1,
+            available: 0
+        }))
+    );
+
+    // Removing nothing always succeeds, even for items not stored
+    assert_that!(a_bag.remove_items(2, 0), equal_to(Ok(ItemStack::default())));
+
+    a_bag.remove_items(1, 18).unwrap();
+    assert_that!(a_bag.utilized_slots(), equal_to(1));
+}
+
+#[rstest]
+fn test_take_stack_clear_and_drain(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(4);
+    for (qty, item) in test_items.iter().enumerate() {
+        a_bag.add_items(ItemStack::new(item.clone(), qty + 1));
+    }
+
+    assert_that!(a_bag.take_stack(3), is(none()));
+    assert_that!(
+        a_bag.take_stack(1),
+        equal_to(Some(ItemStack::new(test_items[1].clone(), 2)))
+    );
+    assert_that!(a_bag.utilized_slots(), equal_to(2));
+
+    // Remaining stacks keep their slot indices
+    assert_that!(a_bag.get_stack(1), is(none()));
+    assert_that!(
+        a_bag.get_stack(2).cloned(),
+        equal_to(Some(ItemStack::new(test_items[2].clone(), 3)))
+    );
+
+    let drained = a_bag.drain_matching(|stack| stack.size() > 2);
+    assert_that!(
+        drained,
+        equal_to(vec![ItemStack::new(test_items[2].clone(), 3)])
+    );
+    assert_that!(a_bag.utilized_slots(), equal_to(1));
+
+    a_bag.clear();
+    assert!(a_bag.is_empty());
+}
+
+#[rstest]
+fn test_transfer_to(test_items: [Item; 3]) {
+    let mut chest = Inventory::new(4);
+    chest.add_items(ItemStack::new(test_items[1].clone(), 10));
+    chest.add_items(ItemStack::new(test_items[2].clone(), 1));
+
+    let mut bag = Inventory::new(1);
+
+    assert_that!(chest.transfer_to(&mut bag, 1, 4), equal_to(Ok(())));
+    assert_that!(chest.quantity_of(1), equal_to(6));
+    assert_that!(bag.quantity_of(1), equal_to(4));
+
+    // Not enough in the chest
+    assert_that!(
+        chest.transfer_to(&mut bag, 1, 7),
+        equal_to(Err(InventoryError::Shortage {
+            id: 1,
+            requested: 7,
+            available: 6
+        }))
+    );
+
+    // No room in the bag--neither side may change
+    let (chest_before, bag_before) = (chest.clone(), bag.clone());
+    assert_that!(
+        chest.transfer_to(&mut bag, 2, 1),
+        equal_to(Err(InventoryError::Rejected {
+            id: 2,
+            requested: 1,
+            accepted: 0,
+            reason: RejectReason::InventoryFull
+        }))
+    );
+    assert_that!(&chest, equal_to(&chest_before));
+    assert_that!(&bag, equal_to(&bag_before));
+}
+
+#[rstest]
+fn test_merge_into(test_items: [Item; 3]) {
+    let mut chest = Inventory::new(4);
+    chest.add_items(ItemStack::new(test_items[0].clone(), 1));
+    chest.add_items(ItemStack::new(test_items[1].clone(), 10));
+
+    let mut small_bag = Inventory::new(1);
+    small_bag.add_items(ItemStack::new(test_items[1].clone(), 2));
+
+    let (chest_before, small_bag_before) = (chest.clone(), small_bag.clone());
+    assert_that!(chest.merge_into(&mut small_bag).is_err(), is(true));
+    assert_that!(&chest, equal_to(&chest_before));
+    assert_that!(&small_bag, equal_to(&small_bag_before));
+
+    let mut big_bag = Inventory::new(2);
+    big_bag.add_items(ItemStack::new(test_items[1].clone(), 2));
+
+    assert_that!(chest.merge_into(&mut big_bag), equal_to(Ok(())));
+    assert!(chest.is_empty());
+    assert_that!(big_bag.utilized_slots(), equal_to(2));
+    assert_that!(big_bag.quantity_of(1), equal_to(12));
+}
+
+///
+/// Freed slots are reused before new ones are opened, but iteration still
+/// follows insertion order.
+///
+#[rstest]
+fn test_freed_slot_is_reused(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(3);
+    for item in test_items.iter() {
+        a_bag.add_items(ItemStack::new(item.clone(), 1));
+    }
+
+    a_bag.take_stack(0);
+    a_bag.add_items(ItemStack::new(test_items[0].clone(), 5));
+
+    assert_that!(
+        a_bag.get_stack(0).cloned(),
+        equal_to(Some(ItemStack::new(test_items[0].clone(), 5)))
+    );
+    assert_that!(a_bag.is_full(), is(true));
+
+    let sizes: Vec<usize> = a_bag.iter().map(ItemStack::size).collect();
+    assert_that!(sizes, equal_to(vec![1, 1, 5]));
+
+    a_bag.swap_slots(0, 2).unwrap();
+    let sizes: Vec<usize> = a_bag.iter().map(ItemStack::size).collect();
+    assert_that!(sizes, equal_to(vec![1, 1, 5]));
+}
+
+#[rstest]
+fn test_slot_addressing(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(10);
+
+    let boots = ItemStack::new(test_items[0].clone(), 1);
+    let tomatoes = ItemStack::new(test_items[1].clone(), 4);
+
+    assert_that!(a_bag.place_stack(8, boots.clone()), equal_to(Ok(None)));
+    assert_that!(a_bag.get_stack(7), is(none()));
+    assert_that!(a_bag.get_stack(8).cloned(), equal_to(Some(boots.clone())));
+    assert_that!(a_bag.utilized_slots(), equal_to(1));
+    assert_that!(a_bag.empty_slots(), equal_to(9));
+
+    // Placing onto an occupied slot hands back what was there
+    assert_that!(
+        a_bag.place_stack(8, tomatoes.clone()),
+        equal_to(Ok(Some(boots.clone())))
+    );
+
+    a_bag.place_stack(2, boots.clone()).unwrap();
+    assert_that!(a_bag.swap_slots(2, 7), equal_to(Ok(())));
+    assert_that!(a_bag.get_stack(2), is(none()));
+    assert_that!(a_bag.get_stack(7).cloned(), equal_to(Some(boots.clone())));
+
+    assert_that!(
+        a_bag.swap_slots(7, 10),
+        equal_to(Err(InventoryError::SlotOutOfRange {
+            slot: 10,
+            total_slots: 10
+        }))
+    );
+    assert_that!(
+        a_bag.place_stack(10, boots),
+        equal_to(Err(InventoryError::SlotOutOfRange {
+            slot: 10,
+            total_slots: 10
+        }))
+    );
+
+    // New stacks fill the lowest empty slot
+    a_bag.add_items(ItemStack::new(test_items[2].clone(), 1));
+    assert_that!(a_bag.get_stack(0).is_some(), is(true));
+
+    assert_that!(a_bag.take_stack(8), equal_to(Some(tomatoes)));
+    assert_that!(a_bag.utilized_slots(), equal_to(2));
+}
+
+#[rstest]
+#[case::empty(0)]
+#[case::over_limit(5)]
+fn test_place_stack_rejects_bad_size(test_items: [Item; 3], #[case] quantity: usize) {
+    let mut small_tomato = test_items[1].clone();
+    small_tomato.set_max_stack_size(4);
+
+    let mut a_bag = Inventory::new(2);
+
+    assert_that!(
+        a_bag.place_stack(0, ItemStack::new(small_tomato, quantity)),
+        equal_to(Err(InventoryError::BadStackSize {
+            id: 1,
+            quantity,
+            max_stack_size: 4
+        }))
+    );
+    assert_that!(a_bag.is_empty(), is(true));
+}
+
+#[rstest]
+fn test_to_display_grid(test_items: [Item; 3]) {
+    let mut a_bag = Inventory::new(3);
+    a_bag
+        .place_stack(1, ItemStack::new(test_items[1].clone(), 2))
+        .unwrap();
+
+    assert_that!(
+        format!("{:#}", a_bag),
+        equal_to(String::from(
+            " -Used 1 of 3 slots\n  [0] (empty)\n  [1] ( 2) Tomato\n  [2] (empty)\n"
+        ))
+    );
+    assert_that!(
+        a_bag.to_string(),
+        equal_to(String::from(" -Used 1 of 3 slots\n  ( 2) Tomato\n"))
+    );
+}
+
+#[rstest]
+fn test_add_items_weight_limit(test_items: [Item; 3]) {
+    let mut iron_ore = Item::new(5, String::from("Iron Ore"));
+    iron_ore.set_weight(2_500);
+
+    let mut a_bag = Inventory::new(3);
+    a_bag.set_max_weight(Some(20_000));
+
+    assert_that!(
+        a_bag.add_items(ItemStack::new(iron_ore.clone(), 6)),
+        equal_to(AddOutcome::NewSlot {
+            accepted: 6,
+            new_slots: 1
+        })
+    );
+
+    // 5 kg of spare capacity fits only two more
+    let outcome = a_bag.add_items(ItemStack::new(iron_ore.clone(), 4));
+    assert_that!(outcome.accepted(), equal_to(2));
+    assert_that!(
+        outcome.reason(),
+        equal_to(Some(RejectReason::WeightExceeded))
+    );
+    assert_that!(a_bag.total_weight(), equal_to(20_000));
+
+    // Weightless items are unaffected
+    assert_that!(
+        a_bag
+            .add_items(ItemStack::new(test_items[1].clone(), 9))
+            .is_complete(),
+        is(true)
+    );
+
+    assert_that!(
+        a_bag.to_string().lines().next(),
+        equal_to(Some(" -Used 2 of 3 slots, 20 of 20 kg"))
+    );
+
+    // Placing a stack counts the weight of the one it replaces
+    assert_that!(
+        a_bag.place_stack(2, ItemStack::new(iron_ore.clone(), 1)),
+        equal_to(Err(InventoryError::Rejected {
+            id: 5,
+            requested: 1,
+            accepted: 0,
+            reason: RejectReason::WeightExceeded
+        }))
+    );
+    let replaced = ItemStack::new(iron_ore.clone(), 8);
+    assert_that!(
+        a_bag.place_stack(0, ItemStack::new(iron_ore, 5)),
+        equal_to(Ok(Some(replaced)))
+    );
+    assert_that!(a_bag.total_weight(), equal_to(12_500));
+}
+
+///
+/// An ore bin accepts ores however its filter identifies them, and reports
+/// other items as forbidden rather than as not fitting.
+///
+#[rstest]
+#[case::id_range("14-16")]
+#[case::category("category:Ore")]
+#[case::tag("tag:smeltable")]
+#[case::any_term("category:potion,tag:smeltable")]
+fn test_add_items_filter(test_items: [Item; 3], #[case] filter: &str) {
+    let mut iron_ore = Item::new(15, String::from("Iron Ore"));
+    iron_ore.set_category(Some("ore"));
+    iron_ore.add_tag("smeltable");
+    iron_ore.set_max_stack_size(8);
+
+    let mut ore_bin = Inventory::new(1);
+    ore_bin.set_filter(ItemFilter::parse(filter).unwrap());
+
+    assert_that!(
+        ore_bin
+            .add_items(ItemStack::new(iron_ore.clone(), 8))
+            .is_complete(),
+        is(true)
+    );
+    assert_that!(
+        ore_bin.add_items(ItemStack::new(iron_ore, 1)).reason(),
+        equal_to(Some(RejectReason::InventoryFull))
+    );
+
+    let tomatoes = ItemStack::new(test_items[1].clone(), 3);
+    assert_that!(
+        ore_bin.add_items(tomatoes.clone()),
+        equal_to(AddOutcome::Rejected {
+            reason: RejectReason::ItemForbidden,
+            stack: tomatoes.clone()
+        })
+    );
+    assert_that!(ore_bin.place_stack(0, tomatoes).is_err(), is(true));
+    assert_that!(ore_bin.utilized_slots(), equal_to(1));
+}
+
+#[rstest]
+fn test_category_and_tag_queries(test_items: [Item; 3]) {
+    let mut iron_ore = Item::new(15, String::from("Iron Ore"));
+    iron_ore.set_category(Some("ore"));
+    iron_ore.add_tag("smeltable");
+
+    let mut coal = Item::new(16, String::from("Coal"));
+    coal.set_category(Some("Ore"));
+    coal.add_tag("fuel");
+    coal.add_tag("Smeltable");
+
+    let mut chest = Inventory::new(5);
+    chest.add_items(ItemStack::new(iron_ore.clone(), 3));
+    chest.add_items(ItemStack::new(test_items[1].clone(), 4));
+    chest.add_items(ItemStack::new(coal.clone(), 2));
+
+    assert_that!(chest.quantity_tagged("SMELTABLE"), equal_to(5));
+    assert_that!(chest.quantity_tagged("fuel"), equal_to(2));
+    assert_that!(chest.quantity_tagged("magical"), equal_to(0));
+
+    let ores: Vec<_> = chest
+        .stacks_in_category("ore")
+        .map(|stack| stack.get_item().get_id())
+        .collect();
+    assert_that!(ores, equal_to(vec![15, 16]));
+
+    let mut fuel_bin = Inventory::new(2);
+    fuel_bin.set_filter(ItemFilter::parse("tag:fuel").unwrap());
+    assert_that!(
+        fuel_bin.add_items(ItemStack::new(coal, 1)).is_complete(),
+        is(true)
+    );
+    assert_that!(
+        fuel_bin.add_items(ItemStack::new(iron_ore, 1)).reason(),
+        equal_to(Some(RejectReason::ItemForbidden))
+    );
+}
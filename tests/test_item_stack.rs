@@ -1,6 +1,7 @@
 use hamcrest2::prelude::*;
 use rstest::{fixture, rstest};
 
+use rust_inventory::items::{format_kilograms, parse_kilograms};
 use rust_inventory::prelude::*;
 
 #[fixture]
@@ -72,3 +73,90 @@ pub fn test_display(shovel: Item, tomato: Item) {
         is(some())
     );
 }
+
+#[rstest]
+pub fn test_add_items_respects_max_stack_size(tomato: Item) {
+    let mut small_tomato = tomato.clone();
+    small_tomato.set_max_stack_size(16);
+
+    let mut a_stack = ItemStack::new(small_tomato, 10);
+
+    assert_that!(a_stack.space_remaining(), equal_to(6));
+    assert_that!(a_stack.add_items(4), equal_to(0));
+    assert_that!(a_stack.add_items(5), equal_to(3));
+    assert_that!(a_stack.size(), equal_to(16));
+    assert_that!(a_stack.is_full(), is(true));
+
+    // Items without a declared limit stack without bound
+    let mut unlimited = ItemStack::new(tomato, 1);
+    assert_that!(unlimited.add_items(usize::MAX - 1), equal_to(0));
+    assert_that!(unlimited.is_full(), is(true));
+}
+
+#[rstest]
+pub fn test_non_stackable(shovel: Item) {
+    let mut unique_shovel = shovel.clone();
+    unique_shovel.set_stackable(false);
+
+    let mut a_stack = ItemStack::new(unique_shovel, 1);
+
+    assert_that!(a_stack.permits_stacking(), is(false));
+    assert_that!(a_stack.add_items(1), equal_to(1));
+    assert_that!(a_stack.size(), equal_to(1));
+
+    assert_that!(ItemStack::new(shovel, 1).permits_stacking(), is(true));
+}
+
+#[rstest]
+pub fn test_is_same_kind(tomato: Item, shovel: Item) {
+    let small = ItemStack::new(tomato.clone(), 3);
+    let large = ItemStack::new(tomato.clone(), 5);
+
+    assert_that!(small.is_same_kind(&large), is(true));
+    assert_that!(&small, is(not(equal_to(&large))));
+
+    let mut renamed = tomato.clone();
+    renamed.set_name(String::from("Cherry Tomato"));
+    assert_that!(small.is_same_kind(&ItemStack::new(renamed, 3)), is(false));
+    assert_that!(small.is_same_kind(&ItemStack::new(shovel, 3)), is(false));
+}
+
+#[rstest]
+#[case("2.5", Some(2_500))]
+#[case("0.125", Some(125))]
+#[case("40", Some(40_000))]
+#[case("1.2345", None)]
+#[case("-1", None)]
+#[case(".5", None)]
+#[case("heavy", None)]
+pub fn test_parse_kilograms(#[case] text: &str, #[case] expected: Option<u64>) {
+    assert_that!(parse_kilograms(text), equal_to(expected));
+}
+
+#[rstest]
+pub fn test_weight(tomato: Item) {
+    let mut heavy_tomato = tomato;
+    heavy_tomato.set_weight(250);
+
+    let a_stack = ItemStack::new(heavy_tomato, 10);
+
+    assert_that!(a_stack.weight(), equal_to(2_500));
+    assert_that!(format_kilograms(a_stack.weight()), equal_to("2.5"));
+    assert_that!(format_kilograms(125), equal_to("0.125"));
+    assert_that!(format_kilograms(40_000), equal_to("40"));
+}
+
+#[rstest]
+pub fn test_category_and_tags_ignore_case(tomato: Item) {
+    let mut apple = tomato;
+    apple.set_category(Some("Äpfel"));
+    apple.add_tag("Süß");
+    apple.add_tag("SÜSS");
+    apple.add_tag("süß");
+
+    assert_that!(apple.get_category(), equal_to(Some("äpfel")));
+    assert_that!(apple.is_in_category("ÄPFEL"), is(true));
+    assert_that!(apple.is_in_category("apfel"), is(false));
+    assert_that!(apple.has_tag("SÜß"), is(true));
+    assert_that!(apple.get_tags().len(), equal_to(2));
+}
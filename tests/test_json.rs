@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use hamcrest2::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::filter::ItemFilter;
+use rust_inventory::json;
+use rust_inventory::prelude::*;
+
+mod common;
+use common::iron_ore;
+
+#[rstest]
+fn test_catalog_round_trip(iron_ore: Item) {
+    let catalog =
+        ItemCatalog::from_items([Item::new(1, String::from("HP Potion")), iron_ore]).unwrap();
+
+    let mut buffer = Vec::new();
+    json::write_catalog(&mut buffer, &catalog).unwrap();
+    let read_back = Parser::read_catalog_json(buffer.as_slice()).unwrap();
+
+    assert_that!(read_back.into_items(), equal_to(catalog.into_items()));
+}
+
+#[rstest]
+fn test_catalog_defaults_and_normalization() {
+    let catalog = Parser::read_catalog_json(
+        r#"[{ "id": 7, "name": "Coal", "category": "ORE", "tags": ["Fuel", "fuel"] }]"#.as_bytes(),
+    )
+    .unwrap();
+
+    let coal = catalog.get(7).unwrap();
+    assert_that!(
+        coal.get_max_stack_size(),
+        equal_to(Item::UNLIMITED_STACK_SIZE)
+    );
+    assert_that!(coal.get_weight(), equal_to(0));
+    assert_that!(coal.get_category(), equal_to(Some("ore")));
+    assert_that!(
+        coal.get_tags().to_vec(),
+        equal_to(vec![String::from("fuel")])
+    );
+}
+
+#[rstest]
+#[case::duplicate_id(r#"[{ "id": 1, "name": "A" }, { "id": 1, "name": "B" }]"#)]
+#[case::duplicate_name(r#"[{ "id": 1, "name": "A" }, { "id": 2, "name": "a" }]"#)]
+#[case::empty_stack(r#"[{ "id": 1, "name": "A", "max_stack_size": 0 }]"#)]
+#[case::missing_name(r#"[{ "id": 1 }]"#)]
+fn test_catalog_rejects_bad_documents(#[case] document: &str) {
+    assert_that!(
+        Parser::read_catalog_json(document.as_bytes()).is_err(),
+        is(true)
+    );
+}
+
+#[rstest]
+fn test_inventories_round_trip(iron_ore: Item) {
+    let mut ore_bin = Inventory::new(4);
+    ore_bin.set_max_weight(Some(100_000));
+    ore_bin.set_filter(ItemFilter::parse("1-9,category:ore").unwrap());
+    ore_bin.place_stack(2, ItemStack::new(iron_ore, 8)).unwrap();
+
+    let mut potions = Inventory::new(2);
+    potions.add_items(ItemStack::new(Item::new(1, String::from("HP Potion")), 3));
+
+    let inventories = vec![ore_bin, potions];
+
+    let mut buffer = Vec::new();
+    json::write_inventories(&mut buffer, &inventories).unwrap();
+    let read_back = Parser::read_inventories_json(buffer.as_slice()).unwrap();
+
+    assert_that!(read_back, equal_to(inventories));
+}
+
+#[rstest]
+#[case::slot_out_of_range(
+    r#"[{ "capacity": 1, "slots": [{ "slot": 1, "item": { "id": 1, "name": "A" }, "quantity": 1 }] }]"#
+)]
+#[case::slot_listed_twice(
+    r#"[{ "capacity": 2, "slots": [
+        { "slot": 0, "item": { "id": 1, "name": "A" }, "quantity": 1 },
+        { "slot": 0, "item": { "id": 1, "name": "A" }, "quantity": 2 }] }]"#
+)]
+#[case::forbidden_item(
+    r#"[{ "capacity": 2, "accept": "5", "slots": [{ "slot": 0, "item": { "id": 1, "name": "A" }, "quantity": 1 }] }]"#
+)]
+#[case::bad_filter(r#"[{ "capacity": 2, "accept": "9-1", "slots": [] }]"#)]
+#[case::zero_quantity(
+    r#"[{ "capacity": 1, "slots": [{ "slot": 0, "item": { "id": 1, "name": "A" }, "quantity": 0 }] }]"#
+)]
+#[case::over_stack_limit(
+    r#"[{ "capacity": 1, "slots": [{ "slot": 0, "item": { "id": 1, "name": "A", "max_stack_size": 4 }, "quantity": 5 }] }]"#
+)]
+#[case::over_weight_limit(
+    r#"[{ "capacity": 2, "max_weight_grams": 1000, "slots": [
+        { "slot": 0, "item": { "id": 1, "name": "A", "weight_grams": 300 }, "quantity": 2 },
+        { "slot": 1, "item": { "id": 1, "name": "A", "weight_grams": 300 }, "quantity": 2 }] }]"#
+)]
+fn test_inventories_reject_bad_documents(#[case] document: &str) {
+    assert_that!(
+        Parser::read_inventories_json(document.as_bytes()).is_err(),
+        is(true)
+    );
+}
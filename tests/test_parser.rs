@@ -1,23 +1,22 @@
-use std::io::{Read, BufRead, BufReader};
+use std::io::BufReader;
 use stringreader::StringReader;
 
 use hamcrest2::prelude::*;
-use rstest::{fixture, rstest};
+use proptest::prelude::*;
+use rstest::rstest;
 
+use rust_inventory::catalog::ItemRef;
+use rust_inventory::error::{ParseError, ParseErrorKind};
+use rust_inventory::filter::ItemFilter;
+use rust_inventory::parser::ParsedLine;
 use rust_inventory::prelude::*;
 
-#[fixture]
-fn test_items() -> [Item; 3] {
-    [
-        Item::new(0, String::from("Diamond Boots")),
-        Item::new(1, String::from("Tomato")),
-        Item::new(2, String::from("Unbreaking Gold Shovel")),
-    ]
-}
+mod common;
+use common::test_items;
 
 #[rstest]
 fn test_parser_one_line(test_items: [Item; 3]) {
-    let mut sreader = StringReader::new("0 Diamond Boots");
+    let sreader = StringReader::new("0 Diamond Boots");
 
     let actual_items = Parser::read_items(BufReader::new(sreader));
 
@@ -26,7 +25,7 @@ fn test_parser_one_line(test_items: [Item; 3]) {
 
 #[rstest]
 fn test_parser_three_lines(test_items: [Item; 3]) {
-    let mut sreader = StringReader::new("0 Diamond Boots\n1 Tomato\n2 Unbreaking Gold Shovel");
+    let sreader = StringReader::new("0 Diamond Boots\n1 Tomato\n2 Unbreaking Gold Shovel");
 
     let actual_items = Parser::read_items(BufReader::new(sreader));
 
@@ -34,3 +33,396 @@ fn test_parser_three_lines(test_items: [Item; 3]) {
     assert_that!(&actual_items[1], equal_to(&test_items[1]));
     assert_that!(&actual_items[2], equal_to(&test_items[2]));
 }
+
+#[rstest]
+fn test_parser_multi_word_name_spacing() {
+    let sreader = StringReader::new("  2   Unbreaking   Gold Shovel  \n");
+
+    let actual_items = Parser::read_items(BufReader::new(sreader));
+
+    assert_that!(actual_items.len(), equal_to(1));
+    assert_that!(
+        actual_items[0].get_name(),
+        equal_to("Unbreaking Gold Shovel")
+    );
+}
+
+#[rstest]
+fn test_parser_reports_malformed_lines(test_items: [Item; 3]) {
+    let sreader = StringReader::new(
+        "0 Diamond Boots\nboots Diamond Boots\n\n1\n1 Tomato\n0 Another Pair of Boots",
+    );
+
+    let (actual_items, errors) = Parser::read_items_with_errors(BufReader::new(sreader));
+
+    assert_that!(&actual_items, equal_to(&test_items[0..2]));
+
+    let errors: Vec<ParseError> = errors.into_iter().collect();
+    assert_that!(
+        errors,
+        equal_to(vec![
+            ParseError::new(ParseErrorKind::BadNumber, 2, 0..5, "boots Diamond Boots"),
+            ParseError::new(
+                ParseErrorKind::MissingToken {
+                    expected: "item name"
+                },
+                4,
+                1..1,
+                "1"
+            ),
+            ParseError::new(
+                ParseErrorKind::DuplicateId { first_line: 1 },
+                6,
+                0..1,
+                "0 Another Pair of Boots"
+            ),
+        ])
+    );
+}
+
+#[rstest]
+fn test_inventory_lines_collect_all_errors() {
+    let sreader = StringReader::new("# 3\n- 1 ten\n- 2  5\n? 4\n# many\n// done");
+
+    let (lines, errors) = Parser::read_inventory_lines_with_errors(BufReader::new(sreader));
+
+    assert_that!(lines.len(), equal_to(6));
+    assert_that!(
+        &lines[0],
+        equal_to(&ParsedLine::InventoryLine {
+            max_size: 3,
+            max_weight: None,
+            filter: ItemFilter::Any
+        })
+    );
+    assert_that!(
+        &lines[2],
+        equal_to(&ParsedLine::ItemStackLine {
+            item: ItemRef::Id(2),
+            quantity: 5
+        })
+    );
+
+    let errors = errors.with_file("bags.txt");
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|error| (error.line, error.kind.clone(), error.offending_text()))
+        .collect();
+    assert_that!(
+        summary,
+        equal_to(vec![
+            (2, ParseErrorKind::BadNumber, "ten"),
+            (4, ParseErrorKind::UnknownDirective, "?"),
+            (5, ParseErrorKind::BadNumber, "many"),
+        ])
+    );
+
+    let first = errors.iter().next().unwrap().to_string();
+    assert_that!(
+        first,
+        equal_to("bags.txt:2:5: expected a number `ten`".to_string())
+    );
+}
+
+#[rstest]
+#[case::counts_characters(ParseErrorKind::BadNumber, 9..12, "x:1:8: expected a number `ten`")]
+#[case::empty_span(
+    ParseErrorKind::MissingToken { expected: "quantity" },
+    12..12,
+    "x:1:11: missing quantity"
+)]
+fn test_parse_error_display(
+    #[case] kind: ParseErrorKind,
+    #[case] span: std::ops::Range<usize>,
+    #[case] expected: &str,
+) {
+    let mut error = ParseError::new(kind, 1, span, "- Épée ten");
+    error.file = Some(String::from("x"));
+
+    assert_that!(error.to_string(), equal_to(expected.to_string()));
+}
+
+#[rstest]
+#[case::bare_capacity("#", ParseErrorKind::MissingToken { expected: "capacity" })]
+#[case::bare_stack("-", ParseErrorKind::MissingToken { expected: "item id" })]
+#[case::missing_quantity("- 5", ParseErrorKind::MissingToken { expected: "quantity" })]
+#[case::negative_quantity("- 5 -1", ParseErrorKind::BadNumber)]
+fn test_inventory_lines_truncated(#[case] input: &str, #[case] expected_kind: ParseErrorKind) {
+    let lines = Parser::read_inventory_lines(BufReader::new(StringReader::new(input)));
+
+    assert_that!(lines.len(), equal_to(1));
+    assert_that!(
+        lines[0].error().map(|error| error.kind.clone()),
+        equal_to(Some(expected_kind))
+    );
+}
+
+#[rstest]
+fn test_inventory_blank_lines_are_valid() {
+    let (lines, errors) = Parser::read_inventory_lines_with_errors(&b"# 2\n\n \t\n- 1 1\n"[..]);
+
+    assert_that!(errors.is_empty(), is(true));
+    assert_that!(lines[1].clone(), equal_to(ParsedLine::BlankLine));
+    assert_that!(lines[2].clone(), equal_to(ParsedLine::BlankLine));
+}
+
+#[rstest]
+fn test_inventory_lines_invalid_utf8() {
+    let lines = Parser::read_inventory_lines(&b"# 2\n- \xff 1\r\n- 1 1\n"[..]);
+
+    assert_that!(lines.len(), equal_to(3));
+    assert_that!(
+        lines[1].error().map(|error| error.kind.clone()),
+        equal_to(Some(ParseErrorKind::InvalidEncoding))
+    );
+    assert_that!(
+        &lines[2],
+        equal_to(&ParsedLine::ItemStackLine {
+            item: ItemRef::Id(1),
+            quantity: 1
+        })
+    );
+}
+
+proptest! {
+    #[test]
+    fn prop_read_inventory_lines_never_panics(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let expected_lines = bytes.split(|b| *b == b'\n').count()
+            - usize::from(bytes.is_empty() || bytes.ends_with(b"\n"));
+
+        let lines = Parser::read_inventory_lines(&bytes[..]);
+
+        prop_assert_eq!(lines.len(), expected_lines);
+    }
+
+    #[test]
+    fn prop_read_items_never_panics(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let (items, errors) = Parser::read_items_with_errors(&bytes[..]);
+
+        prop_assert!(items.len() + errors.len() <= bytes.len());
+    }
+
+    #[test]
+    fn prop_structured_lines_never_panic(
+        lines in proptest::collection::vec("[-#/ 0-9a-z\t]{0,12}", 0..16)
+    ) {
+        let input = lines.join("\n");
+        let parsed = Parser::read_inventory_lines(input.as_bytes());
+
+        for (line, raw) in parsed.iter().zip(lines.iter()) {
+            if let Some(error) = line.error() {
+                prop_assert_eq!(&error.raw_line, raw);
+                prop_assert!(error.span.end <= raw.len());
+            }
+        }
+    }
+}
+
+#[rstest]
+fn test_parser_item_attributes() {
+    let sreader = StringReader::new(
+        "1 Tomato stack=16\n2 Unbreaking Gold Shovel stack=1\n3 Ender Pearl stack=0\n4 Egg size=16",
+    );
+
+    let (actual_items, errors) = Parser::read_items_with_errors(BufReader::new(sreader));
+
+    assert_that!(actual_items.len(), equal_to(2));
+    assert_that!(actual_items[0].get_name(), equal_to("Tomato"));
+    assert_that!(actual_items[0].get_max_stack_size(), equal_to(16));
+    assert_that!(
+        actual_items[1].get_name(),
+        equal_to("Unbreaking Gold Shovel")
+    );
+    assert_that!(actual_items[1].get_max_stack_size(), equal_to(1));
+
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|error| (error.line, error.kind.clone(), error.offending_text()))
+        .collect();
+    assert_that!(
+        summary,
+        equal_to(vec![
+            (3, ParseErrorKind::BadNumber, "0"),
+            (4, ParseErrorKind::UnknownAttribute, "size"),
+        ])
+    );
+}
+
+#[rstest]
+fn test_parser_stackable_attribute() {
+    let sreader = StringReader::new(
+        "1 Tomato stackable=yes\n2 Diamond Boots stackable=no\n3 Stick stackable=maybe",
+    );
+
+    let (actual_items, errors) = Parser::read_items_with_errors(BufReader::new(sreader));
+
+    assert_that!(actual_items.len(), equal_to(2));
+    assert_that!(actual_items[0].is_stackable(), is(true));
+    assert_that!(actual_items[1].is_stackable(), is(false));
+    assert_that!(
+        errors.iter().next().map(|error| error.kind.clone()),
+        equal_to(Some(ParseErrorKind::BadFlag))
+    );
+}
+
+#[rstest]
+fn test_weight_syntax() {
+    let (items, errors) = Parser::read_items_with_errors(BufReader::new(StringReader::new(
+        "5 Iron Ore weight=2.5\n6 Feather weight=light",
+    )));
+    assert_that!(items.len(), equal_to(1));
+    assert_that!(items[0].get_weight(), equal_to(2_500));
+    assert_that!(errors.len(), equal_to(1));
+
+    let lines = Parser::read_inventory_lines(BufReader::new(StringReader::new(
+        "# 4 100\n# 4 0.5\n# 4 lots",
+    )));
+    assert_that!(
+        &lines[0],
+        equal_to(&ParsedLine::InventoryLine {
+            max_size: 4,
+            max_weight: Some(100_000),
+            filter: ItemFilter::Any
+        })
+    );
+    assert_that!(
+        &lines[1],
+        equal_to(&ParsedLine::InventoryLine {
+            max_size: 4,
+            max_weight: Some(500),
+            filter: ItemFilter::Any
+        })
+    );
+    assert_that!(
+        lines[2].error().map(|error| error.offending_text()),
+        equal_to(Some("lots"))
+    );
+}
+
+#[rstest]
+fn test_filter_syntax() {
+    let lines = Parser::read_inventory_lines(BufReader::new(StringReader::new(
+        "# 4 accept=10-19,42\n# 4 2.5 accept=7\n# 4 accept=9-1\n# 4 2.5 3\n# 4 colour=red",
+    )));
+
+    assert_that!(
+        lines[0].clone(),
+        equal_to(ParsedLine::InventoryLine {
+            max_size: 4,
+            max_weight: None,
+            filter: ItemFilter::AnyOf(vec![ItemFilter::Ids(10..=19), ItemFilter::Ids(42..=42)])
+        })
+    );
+    assert_that!(
+        lines[1].clone(),
+        equal_to(ParsedLine::InventoryLine {
+            max_size: 4,
+            max_weight: Some(2_500),
+            filter: ItemFilter::Ids(7..=7)
+        })
+    );
+
+    let kinds: Vec<_> = lines[2..]
+        .iter()
+        .map(|line| {
+            line.error()
+                .map(|error| (error.kind.clone(), error.offending_text()))
+        })
+        .collect();
+    assert_that!(
+        kinds,
+        equal_to(vec![
+            Some((ParseErrorKind::BadFilter, "9-1")),
+            Some((ParseErrorKind::UnknownAttribute, "3")),
+            Some((ParseErrorKind::UnknownAttribute, "colour=red")),
+        ])
+    );
+}
+
+#[rstest]
+fn test_category_and_tag_syntax() {
+    let (items, errors) = Parser::read_items_with_errors(BufReader::new(StringReader::new(
+        "5 Iron Ore category=Ore tags=smeltable,Heavy\n6 Tomato\n7 Coal category=",
+    )));
+
+    assert_that!(items.len(), equal_to(2));
+    assert_that!(items[0].get_category(), equal_to(Some("ore")));
+    assert_that!(
+        items[0].get_tags().to_vec(),
+        equal_to(vec![String::from("smeltable"), String::from("heavy")])
+    );
+    assert_that!(items[1].get_category(), equal_to(None));
+    assert_that!(items[1].get_tags().is_empty(), is(true));
+    assert_that!(
+        errors.iter().next().map(|error| error.kind.clone()),
+        equal_to(Some(ParseErrorKind::MissingToken {
+            expected: "category"
+        }))
+    );
+
+    let lines = Parser::read_inventory_lines(BufReader::new(StringReader::new(
+        "# 4 accept=category:Ore,tag:fuel\n# 4 accept=colour:red",
+    )));
+    assert_that!(
+        lines[0].clone(),
+        equal_to(ParsedLine::InventoryLine {
+            max_size: 4,
+            max_weight: None,
+            filter: ItemFilter::AnyOf(vec![
+                ItemFilter::Category(String::from("ore")),
+                ItemFilter::Tag(String::from("fuel"))
+            ])
+        })
+    );
+    assert_that!(
+        lines[1].error().map(|error| error.kind.clone()),
+        equal_to(Some(ParseErrorKind::BadFilter))
+    );
+}
+
+#[rstest]
+fn test_item_name_references() {
+    let lines = Parser::read_inventory_lines(BufReader::new(StringReader::new(
+        "- \"Diamond   Block\" 4\n- \"Tomato\"\n- \"Tomato 2\n- \"\" 2\n- Tomato 2",
+    )));
+
+    assert_that!(
+        lines[0].clone(),
+        equal_to(ParsedLine::ItemStackLine {
+            item: ItemRef::Name(String::from("Diamond Block")),
+            quantity: 4
+        })
+    );
+
+    let kinds: Vec<_> = lines[1..]
+        .iter()
+        .map(|line| {
+            line.error()
+                .map(|error| (error.kind.clone(), error.offending_text()))
+        })
+        .collect();
+    assert_that!(
+        kinds,
+        equal_to(vec![
+            Some((
+                ParseErrorKind::MissingToken {
+                    expected: "quantity"
+                },
+                ""
+            )),
+            Some((
+                ParseErrorKind::MissingToken {
+                    expected: "closing quote"
+                },
+                ""
+            )),
+            Some((
+                ParseErrorKind::MissingToken {
+                    expected: "item name"
+                },
+                "\"\""
+            )),
+            Some((ParseErrorKind::BadNumber, "Tomato")),
+        ])
+    );
+}
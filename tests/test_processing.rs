@@ -0,0 +1,169 @@
+use hamcrest2::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::catalog::ItemRef;
+use rust_inventory::inventory::RejectReason;
+use rust_inventory::prelude::*;
+use rust_inventory::processing::{self, LogEntry, LogEvent, ProcessingReport};
+
+fn process_fixtures(items_file: &str, inventories_file: &str) -> ProcessingReport {
+    let (catalog, _) = Parser::read_from_file(items_file, Parser::read_catalog_with_errors)
+        .expect("item fixture should exist");
+    let lines = Parser::read_from_file(inventories_file, Parser::read_inventory_lines)
+        .expect("inventory fixture should exist");
+
+    processing::process_inventory_requests(&lines, &catalog)
+}
+
+#[rstest]
+#[case::all_known("itemList-00.txt", "inventoryList-00.txt", vec![3, 6, 5], 15, 0)]
+#[case::one_unknown("itemList-00.txt", "inventoryList-01.txt", vec![3, 6, 2], 15, 1)]
+#[case::mostly_unknown("itemList-01.txt", "inventoryList-02.txt", vec![1, 1, 0], 15, 13)]
+#[case::matching_items("itemList-02.txt", "inventoryList-02.txt", vec![2, 3, 2], 15, 0)]
+fn test_fixture_summary(
+    #[case] items_file: &str,
+    #[case] inventories_file: &str,
+    #[case] used_slots: Vec<usize>,
+    #[case] log_entries: usize,
+    #[case] unknown_items: usize,
+) {
+    let report = process_fixtures(items_file, inventories_file);
+
+    let actual_used: Vec<_> = report
+        .inventories
+        .iter()
+        .map(Inventory::utilized_slots)
+        .collect();
+    assert_that!(actual_used, equal_to(used_slots));
+    assert_that!(
+        report
+            .log
+            .iter()
+            .filter(|entry| entry.event.is_item_event())
+            .count(),
+        equal_to(log_entries)
+    );
+    assert_that!(report.unknown_items(), equal_to(unknown_items));
+}
+
+#[rstest]
+fn test_fixture_contents() {
+    let report = process_fixtures("itemList-00.txt", "inventoryList-00.txt");
+
+    let second = &report.inventories[1];
+    assert_that!(second.total_slots(), equal_to(6));
+    assert_that!(second.quantity_of(4), equal_to(6));
+
+    let second_log: Vec<_> = report.log_for(1).collect();
+    assert_that!(second_log.len(), equal_to(8));
+    assert_that!(
+        (*second_log[6]).clone(),
+        equal_to(LogEntry {
+            line: 14,
+            inventory: Some(1),
+            event: LogEvent::Merged {
+                stack: ItemStack::new(Item::new(4, String::from("Dirt")), 3)
+            }
+        })
+    );
+    assert_that!(second_log[7].line, equal_to(15));
+    assert_that!(
+        second_log[7].event.clone(),
+        equal_to(LogEvent::Comment {
+            text: String::from("// Third Inventory")
+        })
+    );
+}
+
+#[rstest]
+fn test_fixture_overflow_is_logged() {
+    let report = process_fixtures("itemList-00.txt", "inventoryList-01.txt");
+
+    let third_log: Vec<_> = report
+        .log_for(2)
+        .map(|entry| entry.event.to_string())
+        .collect();
+    assert_that!(
+        third_log,
+        equal_to(vec![
+            String::from("Stored    ( 5) MP Potion"),
+            String::from("Stored    ( 4) Diamond Block"),
+            String::from("Discarded ( 1) Diamond (inventory full)"),
+            String::from("Discarded ( 2) Iron Ore (inventory full)"),
+            String::from("Unknown   ( 5) 10"),
+        ])
+    );
+}
+
+#[rstest]
+fn test_stack_lines_before_first_header_are_logged() {
+    let catalog = ItemCatalog::from_items([Item::new(1, String::from("Tomato"))]).unwrap();
+    let lines = Parser::read_inventory_lines("- 1 4\n- 9 2\n# 2\n- 1 3".as_bytes());
+
+    let report = processing::process_inventory_requests(&lines, &catalog);
+
+    assert_that!(report.inventories.len(), equal_to(1));
+    assert_that!(report.inventories[0].quantity_of(1), equal_to(3));
+    assert_that!(report.unknown_items(), equal_to(0));
+    assert_that!(report.log.len(), equal_to(3));
+    assert_that!(
+        report.log[1].clone(),
+        equal_to(LogEntry {
+            line: 2,
+            inventory: None,
+            event: LogEvent::NoInventory {
+                item: ItemRef::Id(9),
+                quantity: 2
+            }
+        })
+    );
+    assert_that!(
+        report.log[0].event.to_string(),
+        equal_to(String::from("Discarded ( 4) 1 (no inventory)"))
+    );
+}
+
+#[rstest]
+fn test_events_carry_line_and_inventory() {
+    let catalog = ItemCatalog::from_items([Item::new(1, String::from("Tomato"))]).unwrap();
+    let lines =
+        Parser::read_inventory_lines("?\n# 1\n- 1 3\n- 2 5\n# 1 accept=2\n- 1 4".as_bytes());
+
+    let report = processing::process_inventory_requests(&lines, &catalog);
+
+    let summary: Vec<_> = report
+        .log
+        .iter()
+        .map(|entry| (entry.line, entry.inventory))
+        .collect();
+    assert_that!(
+        summary,
+        equal_to(vec![(1, None), (3, Some(0)), (4, Some(0)), (6, Some(1))])
+    );
+
+    assert_that!(
+        matches!(report.log[0].event, LogEvent::InvalidLine { .. }),
+        is(true)
+    );
+    assert_that!(
+        report.log[2].event.clone(),
+        equal_to(LogEvent::UnknownItem {
+            item: ItemRef::Id(2),
+            quantity: 5
+        })
+    );
+    assert_that!(
+        report.log[3].event.to_string(),
+        equal_to(String::from("Discarded ( 4) Tomato (item forbidden)"))
+    );
+    assert_that!(
+        matches!(
+            report.log[3].event,
+            LogEvent::Discarded {
+                reason: RejectReason::ItemForbidden,
+                ..
+            }
+        ),
+        is(true)
+    );
+}
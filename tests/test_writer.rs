@@ -0,0 +1,148 @@
+use hamcrest2::prelude::*;
+use proptest::prelude::*;
+use rstest::rstest;
+
+use rust_inventory::filter::ItemFilter;
+use rust_inventory::prelude::*;
+use rust_inventory::processing;
+use rust_inventory::writer::InventoryWriter;
+
+mod common;
+use common::test_items;
+
+///
+/// Parse inventory file text and rebuild the inventories it describes.
+///
+fn read_back(text: &str, catalog: &ItemCatalog) -> Vec<Inventory> {
+    let lines = Parser::read_inventory_lines(text.as_bytes());
+    assert!(lines.iter().all(|line| line.error().is_none()), "{}", text);
+
+    processing::process_inventory_requests(&lines, catalog).inventories
+}
+
+#[rstest]
+fn test_write_format(test_items: [Item; 3]) {
+    let mut chest = Inventory::new(4);
+    chest.set_max_weight(Some(12_500));
+    chest.set_filter(ItemFilter::parse("0-1").unwrap());
+    chest.add_items(ItemStack::new(test_items[1].clone(), 7));
+    chest.add_items(ItemStack::new(test_items[0].clone(), 1));
+
+    let mut bag = Inventory::new(2);
+    bag.add_items(ItemStack::new(test_items[2].clone(), 1));
+
+    let inventories = [chest, bag];
+
+    let mut writer = InventoryWriter::new();
+    assert_that!(
+        writer.write_to_string(&inventories),
+        equal_to(String::from(
+            "# 4 12.5 accept=0-1\n- 1 7\n- 0 1\n# 2\n- 2 1\n"
+        ))
+    );
+
+    writer.set_comments(true);
+    writer.set_item_names(true);
+    assert_that!(
+        writer.write_to_string(&inventories),
+        equal_to(String::from(
+            "// Inventory 1\n# 4 12.5 accept=0-1\n- \"Tomato\" 7\n- \"Diamond Boots\" 1\n\
+             // Inventory 2\n# 2\n- \"Unbreaking Gold Shovel\" 1\n"
+        ))
+    );
+}
+
+#[rstest]
+fn test_unquotable_names_fall_back_to_ids() {
+    let quoted = Item::new(4, String::from("The \"Best\" Sword"));
+    let spaced = Item::new(5, String::from("Wide  Shield"));
+
+    let mut chest = Inventory::new(2);
+    chest.add_items(ItemStack::new(quoted, 1));
+    chest.add_items(ItemStack::new(spaced, 1));
+
+    let mut writer = InventoryWriter::new();
+    writer.set_item_names(true);
+
+    assert_that!(
+        writer.write_to_string(&[chest]),
+        equal_to(String::from("# 2\n- 4 1\n- 5 1\n"))
+    );
+}
+
+///
+/// Items with ids 0 through 5, each with a stack limit (`None` for
+/// unlimited) and a weight in grams.
+///
+fn arb_catalog() -> impl Strategy<Value = ItemCatalog> {
+    proptest::collection::vec((proptest::option::of(1usize..20), 0u64..3_000), 6).prop_map(
+        |settings| {
+            let items = settings
+                .into_iter()
+                .enumerate()
+                .map(|(id, (max_stack_size, weight))| {
+                    let mut item = Item::new(id as u64, format!("Item Number {}", id));
+                    if let Some(max_stack_size) = max_stack_size {
+                        item.set_max_stack_size(max_stack_size);
+                    }
+                    item.set_weight(weight);
+                    item
+                });
+
+            ItemCatalog::from_items(items).unwrap()
+        },
+    )
+}
+
+///
+/// An inventory's capacity, weight limit, filter, and the stacks to add.
+///
+type InventorySpec = (usize, Option<u64>, Option<u64>, Vec<(u64, usize)>);
+
+fn arb_inventory_spec() -> impl Strategy<Value = InventorySpec> {
+    (
+        1usize..6,
+        proptest::option::of(0u64..60_000),
+        proptest::option::of(0u64..6),
+        proptest::collection::vec((0u64..6, 1usize..40), 0..12),
+    )
+}
+
+fn build_inventory(spec: &InventorySpec, catalog: &ItemCatalog) -> Inventory {
+    let (capacity, max_weight, lowest_id, additions) = spec;
+
+    let mut inventory = Inventory::new(*capacity);
+    inventory.set_max_weight(*max_weight);
+    if let Some(lowest_id) = lowest_id {
+        inventory.set_filter(ItemFilter::Ids(*lowest_id..=5));
+    }
+
+    for (id, quantity) in additions {
+        let item = catalog.get(*id).unwrap().clone();
+        inventory.add_items(ItemStack::new(item, *quantity));
+    }
+
+    inventory
+}
+
+proptest! {
+    #[test]
+    fn prop_write_then_parse_round_trips(
+        catalog in arb_catalog(),
+        specs in proptest::collection::vec(arb_inventory_spec(), 0..4),
+        comments in any::<bool>(),
+        item_names in any::<bool>(),
+    ) {
+        let inventories: Vec<Inventory> = specs
+            .iter()
+            .map(|spec| build_inventory(spec, &catalog))
+            .collect();
+
+        let mut writer = InventoryWriter::new();
+        writer.set_comments(comments);
+        writer.set_item_names(item_names);
+        let text = writer.write_to_string(&inventories);
+
+        prop_assert_eq!(read_back(&text, &catalog), inventories);
+    }
+}